@@ -12,8 +12,8 @@ echo "Using CHAIN: $CHAIN"
 # Copy command helper (cross-platform)
 CP_CMD=$(command -v cp &> /dev/null && echo "cp" || echo "copy")
 
-# Determine all contracts under `$CONTRACTS_DIR`
-contracts=($(find $CONTRACTS_DIR -maxdepth 1 -type d -exec test -f {}/Cargo.toml \; -print | xargs -n 1 basename))
+# Determine all contracts under `$CONTRACTS_DIR`, skipping library crates such as `access_control`
+contracts=($(grep -l "#\[ink::contract\]" $CONTRACTS_DIR/*/lib.rs | xargs -n 1 dirname | xargs -n 1 basename))
 echo $contracts
 # Build all contracts
 for i in "${contracts[@]}"
@@ -12,7 +12,7 @@ echo "Using CHAIN: $CHAIN"
 # Copy command helper (cross-platform)
 CP_CMD=$(command -v cp &> /dev/null && echo "cp" || echo "copy")
 
-core_contracts=("mock_nominator" "nomination_agent" "registry" "share_token" "vault")
+core_contracts=("mock_nominator" "multisig" "nomination_agent" "registry" "share_token" "unlock_ticket" "vault")
 
 # Build core contracts
 for i in "${core_contracts[@]}"
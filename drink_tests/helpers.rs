@@ -20,6 +20,22 @@ pub struct Agent {
     pub weight: u64,
 }
 
+#[derive(Debug, PartialEq, scale::Decode)]
+pub enum UnlockStatus {
+    Pending,
+    Redeemed,
+    Cancelled,
+}
+
+#[derive(Debug, scale::Decode)]
+pub struct UnlockRequest {
+    pub creation_time: u64,
+    pub batch_id: u64,
+    pub shares: u128,
+    pub ticket_id: u128,
+    pub status: UnlockStatus,
+}
+
 pub fn update_days(
     mut sess: Session<MinimalRuntime>,
     days: u64,
@@ -84,6 +100,44 @@ pub fn call_update_agents(
     )?;
     Ok(sess)
 }
+pub fn call_update_validators(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    agent: &AccountId32,
+    validators: Vec<String>,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::update_validators"),
+        Some(vec![
+            agent.to_string(),
+            serde_json::to_string(&validators).unwrap(),
+        ]),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+pub fn get_validators(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    agent: &AccountId32,
+) -> Result<(Vec<AccountId32>, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &registry,
+        &AccountId32::new([1u8; 32]),
+        String::from("IRegistry::get_validators"),
+        Some(vec![agent.to_string()]),
+        None,
+        transcoder_registry(),
+    )?;
+    let validators: Result<Result<Vec<AccountId32>, ()>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((validators.unwrap().unwrap(), sess))
+}
 pub fn call_remove_agent(
     sess: Session<MinimalRuntime>,
     registry: &AccountId32,
@@ -131,6 +185,47 @@ pub fn call_stake(
     let balance: Result<u128, drink::errors::LangError> = sess.last_call_return().unwrap();
     Ok((balance.unwrap(), sess))
 }
+pub fn call_stake_for(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    token: &AccountId32,
+    sender: &AccountId32,
+    beneficiary: &AccountId32,
+    amount: u128,
+) -> Result<(u128, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::stake_for"),
+        Some([beneficiary.to_string()].to_vec()),
+        Some(amount),
+        transcoder_vault(),
+    )?;
+
+    query_token_balance(sess, token, beneficiary)
+}
+pub fn call_stake_for_with_referral(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    token: &AccountId32,
+    sender: &AccountId32,
+    beneficiary: &AccountId32,
+    referral_id: &AccountId32,
+    amount: u128,
+) -> Result<(u128, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::stake_for_with_referral"),
+        Some([beneficiary.to_string(), referral_id.to_string()].to_vec()),
+        Some(amount),
+        transcoder_vault(),
+    )?;
+
+    query_token_balance(sess, token, beneficiary)
+}
 pub fn call_request_unlock(
     mut sess: Session<MinimalRuntime>,
     vault: &AccountId32,
@@ -159,111 +254,252 @@ pub fn call_request_unlock(
     let balance: Result<u128, drink::errors::LangError> = sess.last_call_return().unwrap();
     Ok((balance.unwrap(), sess))
 }
+pub fn call_process_batch(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::process_batch"),
+        None,
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+pub fn call_cancel_unlock(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    unlock_id: u64,
+) -> Result<(u128, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::cancel_unlock"),
+        Some([unlock_id.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+
+    let shares: Result<Result<u128, ()>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((shares.unwrap().unwrap(), sess))
+}
+pub fn call_instant_unlock(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    shares: u128,
+    min_azero: u128,
+) -> Result<(u128, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::instant_unlock"),
+        Some([shares.to_string(), min_azero.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+
+    let redeemed: Result<Result<u128, ()>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((redeemed.unwrap().unwrap(), sess))
+}
+pub fn get_buffer_reserve(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+) -> Result<(u128, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess: Session<MinimalRuntime> = call_function(
+        sess,
+        vault,
+        &AccountId32::new([1u8; 32]),
+        String::from("IVault::get_buffer_reserve"),
+        None,
+        None,
+        transcoder_vault(),
+    )
+    .unwrap();
+    let buffer_reserve: Result<u128, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((buffer_reserve.unwrap(), sess))
+}
 
 #[allow(dead_code)]
 pub enum RoleType {
+    Admin,
     AddAgent,
     UpdateAgents,
     RemoveAgent,
     SetCodeHash,
+    Pause,
 }
-pub fn get_role(
+pub fn get_role_members(
     mut sess: Session<MinimalRuntime>,
     registry: &AccountId32,
     role_type: &RoleType,
-) -> Result<(AccountId32, Session<MinimalRuntime>), Box<dyn Error>> {
+) -> Result<(Vec<AccountId32>, Session<MinimalRuntime>), Box<dyn Error>> {
     let role_string = match role_type {
+        RoleType::Admin => "Admin",
         RoleType::AddAgent => "AddAgent",
         RoleType::UpdateAgents => "UpdateAgents",
         RoleType::RemoveAgent => "RemoveAgent",
         RoleType::SetCodeHash => "SetCodeHash",
+        RoleType::Pause => "Pause",
     };
-    sess.call_with_address(registry.clone(), "IRegistry::get_role", &[role_string], None)?;
+    sess.call_with_address(registry.clone(), "IRegistry::get_role_members", &[role_string], None)?;
 
-    let role: Result<AccountId32, drink::errors::LangError> = sess.last_call_return().unwrap();
-    Ok((role.unwrap(), sess))
+    let members: Result<Vec<AccountId32>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((members.unwrap(), sess))
 }
-pub fn get_role_admin(
-    mut sess: Session<MinimalRuntime>,
+pub fn propose_role(
+    sess: Session<MinimalRuntime>,
     registry: &AccountId32,
+    sender: &AccountId32,
     role_type: &RoleType,
-) -> Result<(AccountId32, Session<MinimalRuntime>), Box<dyn Error>> {
+    account: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
     let role_string = match role_type {
+        RoleType::Admin => "Admin",
         RoleType::AddAgent => "AddAgent",
         RoleType::UpdateAgents => "UpdateAgents",
         RoleType::RemoveAgent => "RemoveAgent",
         RoleType::SetCodeHash => "SetCodeHash",
+        RoleType::Pause => "Pause",
     };
-    sess.call_with_address(registry.clone(), "IRegistry::get_role_admin", &[role_string], None)?;
-
-    let admin: Result<AccountId32, drink::errors::LangError> = sess.last_call_return().unwrap();
-    Ok((admin.unwrap(), sess))
+    let sess = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::propose_role"),
+        Some([role_string.to_string(), account.to_string()].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
 }
-pub fn transfer_role(
+pub fn accept_role(
     sess: Session<MinimalRuntime>,
     registry: &AccountId32,
     sender: &AccountId32,
     role_type: &RoleType,
-    new_account: &AccountId32,
 ) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
     let role_string = match role_type {
+        RoleType::Admin => "Admin",
         RoleType::AddAgent => "AddAgent",
         RoleType::UpdateAgents => "UpdateAgents",
         RoleType::RemoveAgent => "RemoveAgent",
         RoleType::SetCodeHash => "SetCodeHash",
+        RoleType::Pause => "Pause",
     };
     let sess = call_function(
         sess,
         &registry,
         &sender,
-        String::from("IRegistry::transfer_role"),
-        Some([role_string.to_string(), new_account.to_string()].to_vec()),
+        String::from("IRegistry::accept_role"),
+        Some([role_string.to_string()].to_vec()),
         None,
         transcoder_registry(),
     )?;
     Ok(sess)
 }
-pub fn transfer_role_admin(
+pub fn revoke_role(
     sess: Session<MinimalRuntime>,
     registry: &AccountId32,
     sender: &AccountId32,
     role_type: &RoleType,
-    new_account: &AccountId32,
+    account: &AccountId32,
 ) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
     let role_string = match role_type {
+        RoleType::Admin => "Admin",
         RoleType::AddAgent => "AddAgent",
         RoleType::UpdateAgents => "UpdateAgents",
         RoleType::RemoveAgent => "RemoveAgent",
         RoleType::SetCodeHash => "SetCodeHash",
+        RoleType::Pause => "Pause",
     };
     let sess = call_function(
         sess,
         &registry,
         &sender,
-        String::from("IRegistry::transfer_role_admin"),
-        Some([role_string.to_string(), new_account.to_string()].to_vec()),
+        String::from("IRegistry::revoke_role"),
+        Some([role_string.to_string(), account.to_string()].to_vec()),
         None,
         transcoder_registry(),
     )?;
     Ok(sess)
 }
-pub fn get_role_adjust_fee(
-    mut sess: Session<MinimalRuntime>,
+pub fn renounce_role(
+    sess: Session<MinimalRuntime>,
+    registry: &AccountId32,
+    sender: &AccountId32,
+    role_type: &RoleType,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let role_string = match role_type {
+        RoleType::Admin => "Admin",
+        RoleType::AddAgent => "AddAgent",
+        RoleType::UpdateAgents => "UpdateAgents",
+        RoleType::RemoveAgent => "RemoveAgent",
+        RoleType::SetCodeHash => "SetCodeHash",
+        RoleType::Pause => "Pause",
+    };
+    let sess = call_function(
+        sess,
+        &registry,
+        &sender,
+        String::from("IRegistry::renounce_role"),
+        Some([role_string.to_string()].to_vec()),
+        None,
+        transcoder_registry(),
+    )?;
+    Ok(sess)
+}
+pub fn call_propose_vault_role(
+    sess: Session<MinimalRuntime>,
     vault: &AccountId32,
-) -> Result<(AccountId32, Session<MinimalRuntime>), Box<dyn Error>> {
-    sess.call_with_address(vault.clone(), "IVault::get_role_adjust_fee", NO_ARGS, None)?;
-
-    let adjust_fee: Result<AccountId32, drink::errors::LangError> = sess.last_call_return().unwrap();
-    Ok((adjust_fee.unwrap(), sess))
+    sender: &AccountId32,
+    role_type: &str,
+    account: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::propose_role"),
+        Some([role_type.to_string(), account.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
 }
-pub fn get_role_fee_to(
+pub fn call_accept_vault_role(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    role_type: &str,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::accept_role"),
+        Some([role_type.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+pub fn get_vault_role_members(
     mut sess: Session<MinimalRuntime>,
     vault: &AccountId32,
-) -> Result<(AccountId32, Session<MinimalRuntime>), Box<dyn Error>> {
-    sess.call_with_address(vault.clone(), "IVault::get_role_fee_to", NO_ARGS, None)?;
+    role_type: &str,
+) -> Result<(Vec<AccountId32>, Session<MinimalRuntime>), Box<dyn Error>> {
+    sess.call_with_address(vault.clone(), "IVault::get_role_members", &[role_type], None)?;
 
-    let fee_to: Result<AccountId32, drink::errors::LangError> = sess.last_call_return().unwrap();
-    Ok((fee_to.unwrap(), sess))
+    let members: Result<Vec<AccountId32>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((members.unwrap(), sess))
 }
 pub fn get_agents(
     mut sess: Session<MinimalRuntime>,
@@ -376,14 +612,12 @@ pub fn query_token_balance(
     Ok((balance.unwrap(), sess))
 }
 pub fn call_redeem(
-    mut sess: Session<MinimalRuntime>,
+    sess: Session<MinimalRuntime>,
     vault: &AccountId32,
     sender: &AccountId32,
     index: u64,
 ) -> Result<(u128, Session<MinimalRuntime>), Box<dyn Error>> {
-    let prev_balance = sess.chain_api().balance(&sender);
-
-    let mut sess = call_function(
+    let sess = call_function(
         sess,
         &vault,
         &sender,
@@ -393,21 +627,18 @@ pub fn call_redeem(
         transcoder_vault(),
     )?;
 
-    let updated_balance = sess.chain_api().balance(&sender);
-    let gained = updated_balance - prev_balance;
-
-    Ok((gained, sess))
+    // AZERO is paid to the ticket holder, which is not necessarily the sender
+    let redeemed: Result<Result<u128, ()>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((redeemed.unwrap().unwrap(), sess))
 }
 
 pub fn call_redeem_with_withdraw(
-    mut sess: Session<MinimalRuntime>,
+    sess: Session<MinimalRuntime>,
     vault: &AccountId32,
     sender: &AccountId32,
     index: u64,
 ) -> Result<(u128, Session<MinimalRuntime>), Box<dyn Error>> {
-    let prev_balance = sess.chain_api().balance(&sender);
-
-    let mut sess = call_function(
+    let sess = call_function(
         sess,
         &vault,
         &sender,
@@ -417,10 +648,267 @@ pub fn call_redeem_with_withdraw(
         transcoder_vault(),
     )?;
 
-    let updated_balance = sess.chain_api().balance(&sender);
-    let gained = updated_balance - prev_balance;
+    // AZERO is paid to the ticket holder, which is not necessarily the sender
+    let redeemed: Result<Result<u128, ()>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((redeemed.unwrap().unwrap(), sess))
+}
+
+pub fn get_claimable_time(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    user: &AccountId32,
+    unlock_id: u64,
+) -> Result<(Option<u64>, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &user,
+        String::from("IVault::get_claimable_time"),
+        Some([user.to_string(), unlock_id.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+    let time: Result<Option<u64>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((time.unwrap(), sess))
+}
 
-    Ok((gained, sess))
+pub fn get_claimable_azero(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    user: &AccountId32,
+    unlock_id: u64,
+) -> Result<(Option<u128>, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &user,
+        String::from("IVault::get_claimable_azero"),
+        Some([user.to_string(), unlock_id.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+    let azero: Result<Option<u128>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((azero.unwrap(), sess))
+}
+
+pub fn get_unlock_requests(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    user: &AccountId32,
+    start: u64,
+    limit: u64,
+) -> Result<(Vec<(u64, UnlockRequest)>, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &user,
+        String::from("IVault::get_unlock_requests"),
+        Some([user.to_string(), start.to_string(), limit.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+    let requests: Result<Vec<(u64, UnlockRequest)>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((requests.unwrap(), sess))
+}
+
+pub fn call_transfer_ticket(
+    sess: Session<MinimalRuntime>,
+    ticket: &AccountId32,
+    sender: &AccountId32,
+    to: &AccountId32,
+    ticket_id: u128,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &ticket,
+        &sender,
+        String::from("PSP34::transfer"),
+        Some([to.to_string(), format!("U128({})", ticket_id), String::from("[]")].to_vec()),
+        None,
+        transcoder_unlock_ticket(),
+    )?;
+    Ok(sess)
+}
+
+pub fn query_ticket_owner(
+    sess: Session<MinimalRuntime>,
+    ticket: &AccountId32,
+    ticket_id: u128,
+) -> Result<(Option<AccountId32>, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &ticket,
+        &AccountId32::new([1u8; 32]),
+        String::from("PSP34::owner_of"),
+        Some([format!("U128({})", ticket_id)].to_vec()),
+        None,
+        transcoder_unlock_ticket(),
+    )?;
+    let owner: Result<Option<AccountId32>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((owner.unwrap(), sess))
+}
+
+pub fn call_claim_referral_rewards(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+) -> Result<(u128, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::claim_referral_rewards"),
+        None,
+        None,
+        transcoder_vault(),
+    )?;
+
+    let shares: Result<Result<u128, ()>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((shares.unwrap().unwrap(), sess))
+}
+
+pub fn get_claimable_referral_rewards(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    referrer: &AccountId32,
+) -> Result<(u128, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &referrer,
+        String::from("IVault::get_claimable_referral_rewards"),
+        Some([referrer.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+    let rewards: Result<u128, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((rewards.unwrap(), sess))
+}
+
+pub fn get_remaining_pool_capacity(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+) -> Result<(u128, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &AccountId32::new([1u8; 32]),
+        String::from("IVault::get_remaining_pool_capacity"),
+        None,
+        None,
+        transcoder_vault(),
+    )?;
+    let capacity: Result<u128, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((capacity.unwrap(), sess))
+}
+
+pub fn get_remaining_account_capacity(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    account: &AccountId32,
+) -> Result<(Option<u128>, Session<MinimalRuntime>), Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &account,
+        String::from("IVault::get_remaining_account_capacity"),
+        Some([account.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+    let capacity: Result<Option<u128>, drink::errors::LangError> = sess.last_call_return().unwrap();
+    Ok((capacity.unwrap(), sess))
+}
+
+pub fn call_pause(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    pause_type: &str,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::pause"),
+        Some([pause_type.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+
+pub fn call_unpause(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    pause_type: &str,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::unpause"),
+        Some([pause_type.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+
+pub fn call_report_slash(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    agent: &AccountId32,
+    staked: u128,
+    unbonding: u128,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::report_slash"),
+        Some([agent.to_string(), staked.to_string(), unbonding.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+
+pub fn call_rebalance(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    max_amount: u128,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::rebalance"),
+        Some([max_amount.to_string()].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+
+pub fn call_complete_rebalance(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::complete_rebalance"),
+        None,
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
 }
 
 pub fn call_withdraw_fees(
@@ -440,6 +928,147 @@ pub fn call_withdraw_fees(
     Ok(sess)
 }
 
+pub fn call_set_fee_recipients(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+    recipients: Vec<(&AccountId32, u16)>,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let recipients = recipients
+        .iter()
+        .map(|(recipient, weight)| format!("({}, {})", recipient, weight))
+        .collect::<Vec<String>>()
+        .join(", ");
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::set_fee_recipients"),
+        Some([format!("[{}]", recipients)].to_vec()),
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+
+pub fn call_distribute_fees(
+    sess: Session<MinimalRuntime>,
+    vault: &AccountId32,
+    sender: &AccountId32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &vault,
+        &sender,
+        String::from("IVault::distribute_fees"),
+        None,
+        None,
+        transcoder_vault(),
+    )?;
+    Ok(sess)
+}
+
+pub fn deploy_multisig(
+    mut sess: Session<MinimalRuntime>,
+    owners: Vec<&AccountId32>,
+    threshold: u32,
+) -> Result<(AccountId32, Session<MinimalRuntime>), Box<dyn Error>> {
+    let owners = owners
+        .iter()
+        .map(|owner| owner.to_string())
+        .collect::<Vec<String>>()
+        .join(", ");
+    let multisig = sess.deploy(
+        bytes_multisig(),
+        "new",
+        &[format!("[{}]", owners), threshold.to_string()],
+        vec![2],
+        None,
+        &transcoder_multisig().unwrap(),
+    )?;
+    sess.set_transcoder(multisig.clone(), &transcoder_multisig().unwrap());
+    Ok((multisig, sess))
+}
+fn to_hex(bytes: &[u8]) -> String {
+    format!("0x{}", bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>())
+}
+pub fn call_multisig_propose(
+    mut sess: Session<MinimalRuntime>,
+    multisig: &AccountId32,
+    sender: &AccountId32,
+    callee: &AccountId32,
+    callee_transcoder: Option<Rc<ContractMessageTranscoder>>,
+    message: &str,
+    args: Vec<String>,
+) -> Result<(u32, Session<MinimalRuntime>), Box<dyn Error>> {
+    // Encoded call is the 4 byte selector followed by the encoded arguments
+    let encoded = callee_transcoder.unwrap().encode(message, args)?;
+    let (selector, input) = encoded.split_at(4);
+
+    sess.call_with_address(multisig.clone(), "IMultisig::get_next_transaction_id", NO_ARGS, None)?;
+    let id: Result<u32, drink::errors::LangError> = sess.last_call_return().unwrap();
+
+    let sess = call_function(
+        sess,
+        &multisig,
+        &sender,
+        String::from("IMultisig::propose"),
+        Some([callee.to_string(), to_hex(selector), to_hex(input), String::from("0")].to_vec()),
+        None,
+        transcoder_multisig(),
+    )?;
+    Ok((id.unwrap(), sess))
+}
+pub fn call_multisig_confirm(
+    sess: Session<MinimalRuntime>,
+    multisig: &AccountId32,
+    sender: &AccountId32,
+    id: u32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &multisig,
+        &sender,
+        String::from("IMultisig::confirm"),
+        Some([id.to_string()].to_vec()),
+        None,
+        transcoder_multisig(),
+    )?;
+    Ok(sess)
+}
+pub fn call_multisig_execute(
+    sess: Session<MinimalRuntime>,
+    multisig: &AccountId32,
+    sender: &AccountId32,
+    id: u32,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let sess = call_function(
+        sess,
+        &multisig,
+        &sender,
+        String::from("IMultisig::execute"),
+        Some([id.to_string()].to_vec()),
+        None,
+        transcoder_multisig(),
+    )?;
+    Ok(sess)
+}
+/// Proposes, confirms by a second owner, and executes a call through the multisig
+pub fn call_through_multisig(
+    sess: Session<MinimalRuntime>,
+    multisig: &AccountId32,
+    owners: (&AccountId32, &AccountId32),
+    callee: &AccountId32,
+    callee_transcoder: Option<Rc<ContractMessageTranscoder>>,
+    message: &str,
+    args: Vec<String>,
+) -> Result<Session<MinimalRuntime>, Box<dyn Error>> {
+    let (id, sess) = call_multisig_propose(sess, multisig, owners.0, callee, callee_transcoder, message, args)?;
+    let sess = call_multisig_confirm(sess, multisig, owners.1, id)?;
+    let sess = call_multisig_execute(sess, multisig, owners.0, id)?;
+    Ok(sess)
+}
+
 pub fn call_function(
     mut sess: Session<MinimalRuntime>,
     contract: &AccountId32,
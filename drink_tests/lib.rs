@@ -20,6 +20,7 @@ mod tests {
         sess: Session<MinimalRuntime>,
         registry: AccountId32,
         share_token: AccountId32,
+        unlock_ticket: AccountId32,
         vault: AccountId32,
         nominators: Vec<AccountId32>,
         validators: Vec<AccountId32>,
@@ -52,6 +53,7 @@ mod tests {
 
         sess.upload(helpers::bytes_registry()).expect("Session should upload registry bytes");
         sess.upload(helpers::bytes_share_token()).expect("Session should upload token bytes");
+        sess.upload(helpers::bytes_unlock_ticket()).expect("Session should upload unlock ticket bytes");
         sess.upload(helpers::bytes_nominator()).expect("Session should upload nominator bytes");
 
         let vault = sess.deploy(
@@ -59,6 +61,7 @@ mod tests {
             "new",
             &[
                 helpers::hash_share_token(),
+                helpers::hash_unlock_ticket(),
                 helpers::hash_registry(),
                 helpers::hash_nominator(),
                 helpers::DAY.to_string(),
@@ -97,6 +100,20 @@ mod tests {
         let share_token = ss.unwrap();
         sess.set_transcoder(share_token.clone(), &helpers::transcoder_share_token().unwrap());
 
+        let mut sess = helpers::call_function(
+            sess,
+            &vault,
+            &bob,
+            String::from("IVault::get_unlock_ticket_contract"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let tt: Result<AccountId32, drink::errors::LangError> = sess.last_call_return().unwrap();
+        let unlock_ticket = tt.unwrap();
+        sess.set_transcoder(unlock_ticket.clone(), &helpers::transcoder_unlock_ticket().unwrap());
+
         sess.set_actor(bob.clone());
 
         // ADD AGENTS
@@ -131,6 +148,7 @@ mod tests {
             sess,
             registry,
             share_token,
+            unlock_ticket,
             vault,
             nominators: vec![agents[0].address.clone(), agents[1].address.clone()],
             validators: vec![validator1, validator2, validator3],
@@ -199,291 +217,324 @@ mod tests {
         };
     }
     #[test]
-    fn test_staking_redeem_flow() -> Result<(), Box<dyn Error>> {
+    fn test_stake_for_mints_to_beneficiary() -> Result<(), Box<dyn Error>> {
         let ctx = setup().unwrap();
+        const STAKE_AMOUNT: u128 = 1_000e12 as u128;
 
-        // Verify nominators
-        let (staked, unbonded, sess) = helpers::query_nominator_balance(ctx.sess, &ctx.nominators[0]).unwrap();
-        assert_eq!(staked, 0, "Nominator #1 should have no staked AZERO");
-        assert_eq!(unbonded, 0, "Nominator #1 should have no unbonded AZERO");
-        let (staked, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        assert_eq!(staked, 0, "Nominator #2 should have no staked AZERO");
-        assert_eq!(unbonded, 0, "Nominator #2 should have no unbonded AZERO");
+        // Bob pays for Alice's stake
+        let (shares, sess) = helpers::call_stake_for(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, &ctx.alice, STAKE_AMOUNT).unwrap();
+        assert_eq!(shares, STAKE_AMOUNT);
+        let (shares, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        assert_eq!(shares, 0);
 
-        // Staking of 5m AZERO
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000_000e10 as u128).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e10 as u128).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000_000e10 as u128).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1_000_000e10 as u128).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1_000_000e10 as u128).unwrap();
+        // Charlie pays for Alice's stake with a referral
+        let (shares, sess) = helpers::call_stake_for_with_referral(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, &ctx.alice, &ctx.dave, STAKE_AMOUNT).unwrap();
+        assert_eq!(shares, STAKE_AMOUNT * 2);
+        let (shares, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.charlie).unwrap();
+        assert_eq!(shares, 0);
 
-        let (staked, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        assert_eq!(
-            staked, 2_500_000e10 as u128,
-            "Nominator #1 should have half AZERO staked"
-        );
-        assert_eq!(unbonded, 0, "Nominator #1 should have no unbonded AZERO");
-        let (_, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        assert_eq!(
-            staked, 2_500_000e10 as u128,
-            "Nominator #2 should have half AZERO staked"
-        );
-        assert_eq!(unbonded, 0, "Nominator #2 should have no unbonded AZERO");
+        let (total_pooled, _sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, STAKE_AMOUNT * 2);
 
-        // Allow fees to accumulate
-        let sess = helpers::update_days(sess, 2);
+        Ok(())
+    }
+    #[test]
+    fn test_stake_for_panic_because_below_threshold() {
+        let ctx = setup().unwrap();
 
-        // Unlock requests of 50k AZERO
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 10_000e10 as u128).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000e10 as u128).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 10_000e10 as u128).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 10_000e10 as u128).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 10_000e10 as u128).unwrap();
+        match helpers::call_stake_for(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, &ctx.alice, 1_000_000 - 100) {
+            Ok(_) => panic!("Should panic because stake is insufficient"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_referral_rewards_flow() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
 
-        let fees_50000_staked_2_days_shares = (50_000e10 as u128) * (2 * helpers::DAY as u128) / helpers::YEAR as u128 * 200 / helpers::BIPS;
-        let (fees_50000_staked_2_days_azero, sess) = helpers::get_azero_from_shares(sess, &ctx.vault, fees_50000_staked_2_days_shares).unwrap();
+        // Half of all fees go to referrers
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_referral_fee_cut"),
+            Some(vec![String::from("5000")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
 
-        // Verify nominators
-        let (staked, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        assert_eq!(staked, (2_500_000e10 - 25_000e10) as u128 + (fees_50000_staked_2_days_azero / 2) + 2);
-        assert_eq!(unbonded, 25_000e10 as u128 - (fees_50000_staked_2_days_azero / 2) - 2);
-        let (staked, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        assert_eq!(staked, (2_500_000e10 - 25_000e10) as u128 + (fees_50000_staked_2_days_azero / 2) + 2);
-        assert_eq!(unbonded, 25_000e10 as u128 - (fees_50000_staked_2_days_azero / 2) - 2);
+        // Alice stakes 1k AZERO referred by Dave
+        let (_, sess) = helpers::call_stake_for_with_referral(sess, &ctx.vault, &ctx.share_token, &ctx.alice, &ctx.alice, &ctx.dave, 1_000e12 as u128).unwrap();
 
-        // Wait for cooldown period to complete
-        let sess = helpers::update_days(sess, 14);
+        let sess = helpers::update_days(sess, 10);
 
-        // Redeem AZERO minus fees
-        let (redeemed, sess) = helpers::call_redeem_with_withdraw(sess, &ctx.vault, &ctx.alice, 0).unwrap();
-        assert_eq!(redeemed, 10_000e10 as u128 + 22 - fees_50000_staked_2_days_azero / 5);
-        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.bob, 0).unwrap();
-        assert_eq!(redeemed, 10_000e10 as u128 + 23 - fees_50000_staked_2_days_azero / 5);
-        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.charlie, 0).unwrap();
-        assert_eq!(redeemed, 10_000e10 as u128 + 23 - fees_50000_staked_2_days_azero / 5);
-        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.dave, 0).unwrap();
-        assert_eq!(redeemed, 10_000e10 as u128 + 23 - fees_50000_staked_2_days_azero / 5);
-        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.ed, 0).unwrap();
-        assert_eq!(redeemed, 10_000e10 as u128 + 23 - fees_50000_staked_2_days_azero / 5);
+        let fees_1000_staked_10_days = (1_000e12 as u128) * 200 / helpers::BIPS * (10 * helpers::DAY as u128) / helpers::YEAR as u128;
+        let referral_rewards = fees_1000_staked_10_days * 5000 / helpers::BIPS;
 
-        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
-        assert_eq!(claimable_fees, 43426511146997);
+        let (claimable, sess) = helpers::get_claimable_referral_rewards(sess, &ctx.vault, &ctx.dave).unwrap();
+        assert_eq!(claimable, referral_rewards);
 
-        let (shares_before, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
-        let sess = helpers::call_withdraw_fees(sess, &ctx.vault, &ctx.bob).unwrap();
-        let (shares_after, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
-        assert_eq!(shares_after - shares_before, claimable_fees);
+        let (claimed, sess) = helpers::call_claim_referral_rewards(sess, &ctx.vault, &ctx.dave).unwrap();
+        assert_eq!(claimed, referral_rewards);
+        let (shares, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.dave).unwrap();
+        assert_eq!(shares, referral_rewards);
+
+        // Remaining fees go to the protocol
+        let (virtual_shares, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(virtual_shares, fees_1000_staked_10_days - referral_rewards);
+
+        // Nothing left to claim
+        match helpers::call_claim_referral_rewards(sess, &ctx.vault, &ctx.dave) {
+            Ok(_) => panic!("Should panic because there are no referral rewards"),
+            Err(_) => (),
+        };
 
         Ok(())
     }
     #[test]
-    fn test_fee_adjustment_success() {
+    fn test_referral_rewards_stop_after_unlock() -> Result<(), Box<dyn Error>> {
         let ctx = setup().unwrap();
+
+        // Half of all fees go to referrers
         let sess = helpers::call_function(
             ctx.sess,
             &ctx.vault,
             &ctx.bob,
-            String::from("IVault::adjust_fee"),
-            Some(vec![String::from("1000")]),
+            String::from("IVault::adjust_referral_fee_cut"),
+            Some(vec![String::from("5000")]),
             None,
             helpers::transcoder_vault(),
         )
-        .unwrap();
+            .unwrap();
+
+        // Alice stakes 1k AZERO referred by Dave
+        let (shares, sess) = helpers::call_stake_for_with_referral(sess, &ctx.vault, &ctx.share_token, &ctx.alice, &ctx.alice, &ctx.dave, 1_000e12 as u128).unwrap();
+
+        let sess = helpers::update_days(sess, 10);
+
+        let fees_1000_staked_10_days = (1_000e12 as u128) * 200 / helpers::BIPS * (10 * helpers::DAY as u128) / helpers::YEAR as u128;
+        let referral_rewards = fees_1000_staked_10_days * 5000 / helpers::BIPS;
+
+        // Alice unlocks all of her referred sA0
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, shares).unwrap();
+
+        // Dave no longer earns on the unlocked sA0
+        let sess = helpers::update_days(sess, 10);
+        let (claimable, _sess) = helpers::get_claimable_referral_rewards(sess, &ctx.vault, &ctx.dave).unwrap();
+        assert_eq!(claimable, referral_rewards);
+
+        Ok(())
+    }
+    #[test]
+    fn test_referral_rewards_stop_after_transfer() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
+
+        // Half of all fees go to referrers
         let sess = helpers::call_function(
-            sess,
+            ctx.sess,
             &ctx.vault,
             &ctx.bob,
-            String::from("IVault::get_fee_percentage"),
+            String::from("IVault::adjust_referral_fee_cut"),
+            Some(vec![String::from("5000")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        // Alice stakes 1k AZERO referred by Dave
+        let (shares, sess) = helpers::call_stake_for_with_referral(sess, &ctx.vault, &ctx.share_token, &ctx.alice, &ctx.alice, &ctx.dave, 1_000e12 as u128).unwrap();
+
+        let sess = helpers::update_days(sess, 10);
+
+        let fees_1000_staked_10_days = (1_000e12 as u128) * 200 / helpers::BIPS * (10 * helpers::DAY as u128) / helpers::YEAR as u128;
+        let referral_rewards = fees_1000_staked_10_days * 5000 / helpers::BIPS;
+
+        // Alice transfers all of her referred sA0 to Charlie
+        let sess = helpers::call_function(
+            sess,
+            &ctx.share_token,
+            &ctx.alice,
+            String::from("PSP22::transfer"),
+            Some(vec![ctx.charlie.to_string(), shares.to_string(), "[]".to_string()]),
             None,
+            helpers::transcoder_share_token(),
+        )
+            .unwrap();
+
+        // Ed settles Alice's referred stake
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.ed,
+            String::from("IVault::sync_referred_stake"),
+            Some(vec![ctx.alice.to_string()]),
             None,
             helpers::transcoder_vault(),
         )
-        .unwrap();
-        let res: Result<u16, drink::errors::LangError> = sess.last_call_return().unwrap();
-        assert_eq!(res.unwrap(), 1000)
+            .unwrap();
+
+        // Dave no longer earns on the transferred sA0
+        let sess = helpers::update_days(sess, 10);
+        let (claimable, _sess) = helpers::get_claimable_referral_rewards(sess, &ctx.vault, &ctx.dave).unwrap();
+        assert_eq!(claimable, referral_rewards);
+
+        Ok(())
     }
     #[test]
-    fn test_fee_adjustment_panic_because_caller_restricted() {
+    fn test_stake_for_with_referral_panic_because_self_referral() {
         let ctx = setup().unwrap();
-        match helpers::call_function(
-            ctx.sess,
-            &ctx.vault,
-            &ctx.ed, // not bob
-            String::from("IVault::adjust_fee"),
-            Some(vec![String::from("1234")]),
-            None,
-            helpers::transcoder_vault(),
-        ) {
-            Ok(_) => panic!("Should panic because caller does not have adjust fees role (Bob)"),
+
+        match helpers::call_stake_for_with_referral(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, &ctx.alice, &ctx.alice, 1_000e12 as u128) {
+            Ok(_) => panic!("Should panic because Alice cannot refer herself"),
             Err(_) => (),
-        }
+        };
     }
     #[test]
-    fn test_fee_adjustment_panic_because_overflow() {
+    fn test_stake_for_with_referral_panic_because_referrer_taken_over() {
+        let ctx = setup().unwrap();
+
+        // Alice stakes 1k AZERO referred by Dave
+        let (_, sess) = helpers::call_stake_for_with_referral(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, &ctx.alice, &ctx.dave, 1_000e12 as u128).unwrap();
+
+        // Charlie cannot move Alice's referred sA0 to Ed
+        match helpers::call_stake_for_with_referral(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, &ctx.alice, &ctx.ed, 1_000e12 as u128) {
+            Ok(_) => panic!("Should panic because only Alice can change her referrer"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_adjust_referral_fee_cut_panic_because_caller_restricted() {
         let ctx = setup().unwrap();
+
         match helpers::call_function(
             ctx.sess,
             &ctx.vault,
             &ctx.alice,
-            String::from("IVault::adjust_fee"),
-            Some(vec![String::from("10000")]), // equal to BIPS
+            String::from("IVault::adjust_referral_fee_cut"),
+            Some(vec![String::from("5000")]),
             None,
             helpers::transcoder_vault(),
         ) {
-            Ok(_) => panic!("Should panic because new fee is too large"),
+            Ok(_) => panic!("Should panic because caller is restricted"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_withdraw_fees_after_one_second_success() {
+    fn test_max_total_pooled_panic_because_limit_reached() {
         let ctx = setup().unwrap();
 
-        const STAKE_AMOUNT: u128 = 10_000e10 as u128;
-
-        // default annualized fee of 2%
-        const EXPECTED_FEES: u128 = STAKE_AMOUNT * (helpers::SECOND as u128) / (helpers::YEAR as u128) * 200 / helpers::BIPS;
-
-        // Stake 10k AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, STAKE_AMOUNT).unwrap();
-
-        let sess = helpers::update_in_milliseconds(sess, helpers::SECOND);
-
-        // Verify claimable fees
-        let (shares_before, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
-        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
-        assert_eq!(claimable_fees, EXPECTED_FEES);
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_max_total_pooled"),
+            Some(vec![(1_500e12 as u128).to_string()]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
 
-        // Withdraw fees
-        let sess = helpers::call_withdraw_fees(sess, &ctx.vault, &ctx.bob).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (capacity, sess) = helpers::get_remaining_pool_capacity(sess, &ctx.vault).unwrap();
+        assert_eq!(capacity, 500e12 as u128);
 
-        // Verify shares
-        let (shares_after, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
-        assert_eq!(shares_after - shares_before, EXPECTED_FEES);
+        match helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 600e12 as u128) {
+            Ok(_) => panic!("Should panic because pool capacity is exceeded"),
+            Err(_) => (),
+        };
     }
     #[test]
-    fn test_withdraw_fees_after_one_day_success() {
+    fn test_max_account_shares_panic_because_limit_reached() {
         let ctx = setup().unwrap();
 
-        const STAKE_AMOUNT: u128 = 10_000e10 as u128;
-        const EXPECTED_FEES: u128 = STAKE_AMOUNT * (helpers::DAY as u128) / (helpers::YEAR as u128) * 200 / helpers::BIPS;
-
-        // Stake 10k AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, STAKE_AMOUNT).unwrap();
-
-        let sess = helpers::update_days(sess, 1);
-
-        // Verify claimable fees
-        let (shares_before, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
-        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
-        assert_eq!(claimable_fees, EXPECTED_FEES);
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_max_account_shares"),
+            Some(vec![format!("Some({})", 1_000e12 as u128)]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
 
-        // Withdraw fees
-        let sess = helpers::call_withdraw_fees(sess, &ctx.vault, &ctx.bob).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (capacity, sess) = helpers::get_remaining_account_capacity(sess, &ctx.vault, &ctx.alice).unwrap();
+        assert_eq!(capacity, Some(0));
+        let (capacity, sess) = helpers::get_remaining_account_capacity(sess, &ctx.vault, &ctx.bob).unwrap();
+        assert_eq!(capacity, Some(1_000e12 as u128));
 
-        // Verify shares
-        let (shares_after, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
-        assert_eq!(shares_after - shares_before, EXPECTED_FEES); // annualized fee of 2%
+        // Limit also applies when staking on behalf of the account
+        match helpers::call_stake_for(sess, &ctx.vault, &ctx.share_token, &ctx.bob, &ctx.alice, 1e12 as u128) {
+            Ok(_) => panic!("Should panic because account capacity is exceeded"),
+            Err(_) => (),
+        };
     }
     #[test]
-    fn test_withdraw_fees_after_one_year_success() {
+    fn test_adjust_limits_panic_because_caller_restricted() {
         let ctx = setup().unwrap();
 
-        const STAKE_AMOUNT: u128 = 10_000e10 as u128;
-        const EXPECTED_FEES: u128 = STAKE_AMOUNT * 200 / helpers::BIPS;
+        match helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.alice,
+            String::from("IVault::adjust_max_total_pooled"),
+            Some(vec![(1_500e12 as u128).to_string()]),
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_adjust_cooldown_shortens_claimable_time() -> Result<(), Box<dyn Error>> {
+        let mut ctx = setup().unwrap();
+        let start = ctx.sess.chain_api().get_timestamp();
 
-        // Stake 10k AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, STAKE_AMOUNT).unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 100e12 as u128).unwrap();
 
-        // Verify claimable fees
-        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
-        assert_eq!(claimable_fees, 0);
+        // Unprocessed batch assumes processing after the batch interval
+        let (claimable_time, sess) = helpers::get_claimable_time(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        assert_eq!(claimable_time, Some(start + helpers::DAY + 14 * helpers::DAY));
 
-        let sess = helpers::update_in_milliseconds(sess, helpers::YEAR);
+        // Bonding duration reduced by governance
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_cooldown"),
+            Some(vec![helpers::DAY.to_string(), String::from("7")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
 
-        // Verify claimable fees
-        let (shares_before, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
-        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
-        assert_eq!(claimable_fees, EXPECTED_FEES);
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+        let (claimable_time, sess) = helpers::get_claimable_time(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        assert_eq!(claimable_time, Some(start + helpers::DAY + 7 * helpers::DAY));
 
-        // Withdraw fees
-        let sess = helpers::call_withdraw_fees(sess, &ctx.vault, &ctx.bob).unwrap();
+        let sess = helpers::update_days(sess, 7);
+        let (_, sess) = helpers::call_redeem_with_withdraw(sess, &ctx.vault, &ctx.alice, 0).unwrap();
 
-        // Verify shares
-        let (shares_after, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
-        assert_eq!(shares_after - shares_before, EXPECTED_FEES);
+        // Redeemed requests are no longer claimable
+        let (claimable_time, _sess) = helpers::get_claimable_time(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        assert_eq!(claimable_time, None);
+
+        Ok(())
     }
     #[test]
-    fn test_withdraw_fees_after_adjusted_fee() {
-        const STAKE: u128 = 10_000e10 as u128;
-        const ONE_DAY_FEE_2_PERCENT: u128 = STAKE * helpers::DAY as u128 / helpers::YEAR as u128 * 2_00 / helpers::BIPS;
-        const ONE_DAY_FEE_4_PERCENT: u128 = STAKE * helpers::DAY as u128 / helpers::YEAR as u128 * 4_00 / helpers::BIPS;
-
+    fn test_adjust_cooldown_panic_because_caller_restricted() {
         let ctx = setup().unwrap();
 
-        // Initial stake
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, STAKE).unwrap();
-
-        // 2% fee for 1 day
-        let sess = helpers::update_days(sess, 1);
-        let (expected_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
-        assert_eq!(
-            expected_fees, ONE_DAY_FEE_2_PERCENT,
-            "Should have 2% fee for 1 day"
-        );
-
-        // Adjust fee to 4%
-        let sess = helpers::call_function(
-            sess,
-            &ctx.vault,
-            &ctx.bob,
-            String::from("IVault::adjust_fee"),
-            Some(vec![String::from("400")]), // 4% in helpers::BIPS
-            None,
-            helpers::transcoder_vault(),
-        )
-        .unwrap();
-
-        // 4% fee for 1 day
-        let sess = helpers::update_days(sess, 1);
-        let (expected_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
-        assert_eq!(
-            expected_fees,
-            ONE_DAY_FEE_2_PERCENT + ONE_DAY_FEE_4_PERCENT + 599666, // compounding
-            "Should show 2% fee for 1 day and 4% fee for 1 day",
-        );
-
-        let (shares_before, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
-        let sess = helpers::call_withdraw_fees(sess, &ctx.vault, &ctx.bob).unwrap();
-        let (shares_after, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
-        assert_eq!(
-            shares_after - shares_before,
-            ONE_DAY_FEE_2_PERCENT + ONE_DAY_FEE_4_PERCENT + 599666, // compounding,
-            "Should withdraw 2% fee for 1 day and 4% fee for 1 day"
-        );
-    }
-    #[test]
-    fn test_withdraw_fees_panic_because_caller_restricted() {
-        let ctx = setup().unwrap();
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000).unwrap();
-        let sess = helpers::update_days(sess, 365);
-        match helpers::call_withdraw_fees(
-            sess,
-            &ctx.vault,
-            &ctx.ed, // not bob
-        ) {
-            Ok(_) => panic!("Should panic because caller does not have the fee to role (Bob)"),
-            Err(_) => (),
-        };
-    }
-    #[test]
-    fn test_vault_transfer_role_adjust_fee_panic_because_caller_restricted() {
-        let ctx = setup().unwrap();
         match helpers::call_function(
             ctx.sess,
             &ctx.vault,
-            &ctx.alice, // not bob
-            String::from("IVault::transfer_role_adjust_fee"),
-            Some([ctx.charlie.to_string()].to_vec()),
+            &ctx.alice,
+            String::from("IVault::adjust_cooldown"),
+            Some(vec![helpers::DAY.to_string(), String::from("7")]),
             None,
             helpers::transcoder_vault(),
         ) {
@@ -492,599 +543,2755 @@ mod tests {
         };
     }
     #[test]
-    fn test_vault_transfer_role_adjust_fee_flow() {
+    fn test_request_unlock_panic_because_below_minimum() {
         let ctx = setup().unwrap();
 
-        let (adjust_fee, sess) = helpers::get_role_adjust_fee(ctx.sess, &ctx.vault).unwrap();
-        assert_eq!(adjust_fee, ctx.bob);
-
-        // Transfer role to Charlie
         let sess = helpers::call_function(
-            sess,
+            ctx.sess,
             &ctx.vault,
-            &adjust_fee,
-            String::from("IVault::transfer_role_adjust_fee"),
-            Some([ctx.charlie.to_string()].to_vec()),
+            &ctx.bob,
+            String::from("IVault::adjust_minimum_unlock"),
+            Some(vec![(10e12 as u128).to_string()]),
             None,
             helpers::transcoder_vault(),
         )
             .unwrap();
 
-        let (adjust_fee, _sess) = helpers::get_role_adjust_fee(sess, &ctx.vault).unwrap();
-        assert_eq!(adjust_fee, ctx.charlie);
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 10e12 as u128).unwrap();
+
+        match helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1e12 as u128) {
+            Ok(_) => panic!("Should panic because unlock is below the minimum"),
+            Err(_) => (),
+        };
     }
     #[test]
-    fn test_vault_transfer_role_fee_to_panic_because_caller_restricted() {
+    fn test_adjust_minimum_stake_panic_because_below_bond_extra() {
         let ctx = setup().unwrap();
+
+        // Two weighted agents require at least 2 * 100_000 AZERO
         match helpers::call_function(
             ctx.sess,
             &ctx.vault,
-            &ctx.alice, // not bob
-            String::from("IVault::transfer_role_fee_to"),
-            Some([ctx.charlie.to_string()].to_vec()),
+            &ctx.bob,
+            String::from("IVault::adjust_minimum_stake"),
+            Some(vec![String::from("150000")]),
             None,
             helpers::transcoder_vault(),
         ) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+            Ok(_) => panic!("Should panic because agents cannot bond the minimum"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_vault_transfer_role_fee_to_flow() {
+    fn test_adjust_minimum_stake_panic_because_caller_restricted() {
         let ctx = setup().unwrap();
 
-        let (fee_to, sess) = helpers::get_role_fee_to(ctx.sess, &ctx.vault).unwrap();
-        assert_eq!(fee_to, ctx.bob);
-
-        // Transfer role to Charlie
-        let sess = helpers::call_function(
-            sess,
+        match helpers::call_function(
+            ctx.sess,
             &ctx.vault,
-            &fee_to,
-            String::from("IVault::transfer_role_fee_to"),
-            Some([ctx.charlie.to_string()].to_vec()),
+            &ctx.alice,
+            String::from("IVault::adjust_minimum_stake"),
+            Some(vec![(1e12 as u128).to_string()]),
             None,
             helpers::transcoder_vault(),
-        )
-            .unwrap();
-
-        let (fee_to, _sess) = helpers::get_role_fee_to(sess, &ctx.vault).unwrap();
-        assert_eq!(fee_to, ctx.charlie);
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
     }
     #[test]
-    fn test_nominator_add_agent_role_flow() {
+    fn test_pause_guardian_pauses_and_admin_unpauses() {
         let ctx = setup().unwrap();
 
-        // Check roles
-        let (role, sess) = helpers::get_role(ctx.sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
-        assert_eq!(role, ctx.bob);
-        let (admin, sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
-        assert_eq!(admin, ctx.bob);
+        // Bob is both the pause guardian and the admin, hand the guardian role to Alice
+        let sess = helpers::call_propose_vault_role(ctx.sess, &ctx.vault, &ctx.bob, "PauseGuardian", &ctx.alice).unwrap();
+        let sess = helpers::call_accept_vault_role(sess, &ctx.vault, &ctx.alice, "PauseGuardian").unwrap();
 
-        // Bob (admin) transfers role to Charlie
-        let sess = helpers::transfer_role(sess, &ctx.registry, &admin, &helpers::RoleType::AddAgent, &ctx.charlie).unwrap();
-        // Bob (admin) transfers admin to Charlie
-        let sess = helpers::transfer_role_admin(sess, &ctx.registry, &admin, &helpers::RoleType::AddAgent, &ctx.charlie).unwrap();
+        let sess = helpers::call_pause(sess, &ctx.vault, &ctx.alice, "Stake").unwrap();
 
-        // Check roles
-        let (role, sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
-        assert_eq!(role, ctx.charlie);
-        let (admin, _sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
-        assert_eq!(admin, ctx.charlie);
+        // Only the admin can unpause
+        let sess = helpers::call_unpause(sess, &ctx.vault, &ctx.bob, "Stake").unwrap();
+        let (_, _sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000e12 as u128).unwrap();
     }
     #[test]
-    fn test_nominator_add_agent_role_panic_on_transfer_role_because_caller_not_admin() {
+    fn test_stake_panic_because_paused() {
         let ctx = setup().unwrap();
 
-        // Charlie (not admin) cannot transfer role
-        match helpers::transfer_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+        let sess = helpers::call_pause(ctx.sess, &ctx.vault, &ctx.bob, "Stake").unwrap();
+
+        match helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000e12 as u128) {
+            Ok(_) => panic!("Should panic because staking is paused"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_add_agent_role_panic_on_transfer_admin_because_caller_not_admin() {
+    fn test_unpause_panic_because_caller_restricted() {
         let ctx = setup().unwrap();
 
-        // Charlie (not admin) cannot transfer admin
-        match helpers::transfer_role_admin(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
+        let sess = helpers::call_pause(ctx.sess, &ctx.vault, &ctx.bob, "Stake").unwrap();
+
+        match helpers::call_unpause(sess, &ctx.vault, &ctx.alice, "Stake") {
             Ok(_) => panic!("Should panic because caller is restricted"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_update_agents_role_flow() {
+    fn test_redeem_while_unlock_paused() -> Result<(), Box<dyn Error>> {
         let ctx = setup().unwrap();
 
-        // Check roles
-        let (role, sess) = helpers::get_role(ctx.sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
-        assert_eq!(role, ctx.bob);
-        let (admin, sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
-        assert_eq!(admin, ctx.bob);
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 100e12 as u128).unwrap();
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+        let sess = helpers::update_days(sess, 14);
 
-        // Bob (admin) transfers role to Charlie
-        let sess = helpers::transfer_role(sess, &ctx.registry, &admin, &helpers::RoleType::UpdateAgents, &ctx.charlie).unwrap();
-        // Bob (admin) transfers admin to Charlie
-        let sess = helpers::transfer_role_admin(sess, &ctx.registry, &admin, &helpers::RoleType::UpdateAgents, &ctx.charlie).unwrap();
+        // Pausing every operation does not lock matured unlock requests
+        let sess = helpers::call_pause(sess, &ctx.vault, &ctx.bob, "Stake").unwrap();
+        let sess = helpers::call_pause(sess, &ctx.vault, &ctx.bob, "Unlock").unwrap();
+        let sess = helpers::call_pause(sess, &ctx.vault, &ctx.bob, "Compound").unwrap();
 
-        // Check roles
-        let (role, sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
-        assert_eq!(role, ctx.charlie);
-        let (admin, _sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
-        assert_eq!(admin, ctx.charlie);
-    }
-    #[test]
-    fn test_nominator_update_agents_role_panic_on_transfer_role_because_caller_not_admin() {
-        let ctx = setup().unwrap();
+        let (redeemed, sess) = helpers::call_redeem_with_withdraw(sess, &ctx.vault, &ctx.bob, 0).unwrap();
+        assert_eq!(redeemed, 99994524598242); // 100 AZERO less 1 day of fees
 
-        // Charlie (not admin) cannot transfer role
-        match helpers::transfer_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::UpdateAgents, &ctx.dave) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+        // New unlock requests are blocked
+        match helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 100e12 as u128) {
+            Ok(_) => panic!("Should panic because unlocking is paused"),
             Err(_) => (),
         };
+
+        Ok(())
     }
     #[test]
-    fn test_nominator_update_agents_role_panic_on_transfer_admin_because_caller_not_admin() {
+    fn test_add_agent_panic_because_paused() {
         let ctx = setup().unwrap();
 
-        // Charlie (not admin) cannot transfer admin
-        match helpers::transfer_role_admin(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::UpdateAgents, &ctx.dave) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            String::from("IRegistry::pause"),
+            Some(vec![String::from("AddAgent")]),
+            None,
+            helpers::transcoder_registry(),
+        )
+            .unwrap();
+
+        match helpers::call_add_agent(sess, &ctx.registry, &ctx.bob, &ctx.bob, &ctx.validators[0], 100e12 as u128) {
+            Ok(_) => panic!("Should panic because adding agents is paused"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_remove_agent_role_flow() {
+    fn test_staking_redeem_flow() -> Result<(), Box<dyn Error>> {
         let ctx = setup().unwrap();
 
-        // Check roles
-        let (role, sess) = helpers::get_role(ctx.sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
-        assert_eq!(role, ctx.bob);
-        let (admin, sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
-        assert_eq!(admin, ctx.bob);
+        // Verify nominators
+        let (staked, unbonded, sess) = helpers::query_nominator_balance(ctx.sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(staked, 0, "Nominator #1 should have no staked AZERO");
+        assert_eq!(unbonded, 0, "Nominator #1 should have no unbonded AZERO");
+        let (staked, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(staked, 0, "Nominator #2 should have no staked AZERO");
+        assert_eq!(unbonded, 0, "Nominator #2 should have no unbonded AZERO");
 
-        // Bob (admin) transfers role to Charlie
-        let sess = helpers::transfer_role(sess, &ctx.registry, &admin, &helpers::RoleType::RemoveAgent, &ctx.charlie).unwrap();
-        // Bob (admin) transfers admin to Charlie
-        let sess = helpers::transfer_role_admin(sess, &ctx.registry, &admin, &helpers::RoleType::RemoveAgent, &ctx.charlie).unwrap();
+        // Staking of 5m AZERO
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000_000e10 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000e10 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000_000e10 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1_000_000e10 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1_000_000e10 as u128).unwrap();
 
-        // Check roles
-        let (role, sess) = helpers::get_role(sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
-        assert_eq!(role, ctx.charlie);
-        let (admin, _sess) = helpers::get_role_admin(sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
-        assert_eq!(admin, ctx.charlie);
-    }
-    #[test]
-    fn test_nominator_remove_agent_role_panic_on_transfer_role_because_caller_not_admin() {
-        let ctx = setup().unwrap();
+        let (staked, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(
+            staked, 2_500_000e10 as u128,
+            "Nominator #1 should have half AZERO staked"
+        );
+        assert_eq!(unbonded, 0, "Nominator #1 should have no unbonded AZERO");
+        let (_, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(
+            staked, 2_500_000e10 as u128,
+            "Nominator #2 should have half AZERO staked"
+        );
+        assert_eq!(unbonded, 0, "Nominator #2 should have no unbonded AZERO");
 
-        // Charlie (not admin) cannot transfer role
-        match helpers::transfer_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
-            Err(_) => (),
-        };
+        // Allow fees to accumulate
+        let sess = helpers::update_days(sess, 2);
+
+        // Unlock requests of 50k AZERO
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 10_000e10 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000e10 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 10_000e10 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 10_000e10 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 10_000e10 as u128).unwrap();
+
+        // Unbond the batch
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        let fees_50000_staked_2_days_shares = (50_000e10 as u128) * (2 * helpers::DAY as u128) / helpers::YEAR as u128 * 200 / helpers::BIPS;
+        let (fees_50000_staked_2_days_azero, sess) = helpers::get_azero_from_shares(sess, &ctx.vault, fees_50000_staked_2_days_shares).unwrap();
+
+        // Verify nominators
+        let (staked, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(staked, (2_500_000e10 - 25_000e10) as u128 + (fees_50000_staked_2_days_azero / 2) + 1);
+        assert_eq!(unbonded, 25_000e10 as u128 - (fees_50000_staked_2_days_azero / 2) - 1);
+        let (staked, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(staked, (2_500_000e10 - 25_000e10) as u128 + (fees_50000_staked_2_days_azero / 2) + 1);
+        assert_eq!(unbonded, 25_000e10 as u128 - (fees_50000_staked_2_days_azero / 2) - 1);
+
+        // Wait for cooldown period to complete
+        let sess = helpers::update_days(sess, 14);
+
+        // Redeem AZERO minus fees
+        let (redeemed, sess) = helpers::call_redeem_with_withdraw(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        assert_eq!(redeemed, 10_000e10 as u128 - 1 - fees_50000_staked_2_days_azero / 5);
+        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.bob, 0).unwrap();
+        assert_eq!(redeemed, 10_000e10 as u128 - 1 - fees_50000_staked_2_days_azero / 5);
+        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.charlie, 0).unwrap();
+        assert_eq!(redeemed, 10_000e10 as u128 - 1 - fees_50000_staked_2_days_azero / 5);
+        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.dave, 0).unwrap();
+        assert_eq!(redeemed, 10_000e10 as u128 - 1 - fees_50000_staked_2_days_azero / 5);
+        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.ed, 0).unwrap();
+        assert_eq!(redeemed, 10_000e10 as u128 - 1 - fees_50000_staked_2_days_azero / 5);
+
+        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(claimable_fees, 43426511146997);
+
+        let (shares_before, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        let sess = helpers::call_withdraw_fees(sess, &ctx.vault, &ctx.bob).unwrap();
+        let (shares_after, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        assert_eq!(shares_after - shares_before, claimable_fees);
+
+        Ok(())
     }
     #[test]
-    fn test_nominator_remove_agent_role_panic_on_transfer_admin_because_caller_not_admin() {
+    fn test_fee_adjustment_success() {
         let ctx = setup().unwrap();
-
-        // Charlie (not admin) cannot transfer admin
-        match helpers::transfer_role_admin(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
-            Err(_) => (),
-        };
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_fee"),
+            Some(vec![String::from("1000")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+        let sess = helpers::update_days(sess, 7);
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::execute_fee"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::get_fee_percentage"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+        let res: Result<u16, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert_eq!(res.unwrap(), 1000)
     }
     #[test]
-    fn test_nominator_addition_panic_because_caller_restricted() {
+    fn test_fee_adjustment_panic_because_timelocked() {
         let ctx = setup().unwrap();
-
-        match helpers::call_add_agent(
+        let sess = helpers::call_function(
             ctx.sess,
-            &ctx.registry,
-            &ctx.charlie, // does not have `helpers::RoleType::AddAgent`
-            &ctx.charlie,
-            &ctx.validators[2],
-            100e12 as u128,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_fee"),
+            Some(vec![String::from("1000")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+        let sess = helpers::update_days(sess, 6);
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::execute_fee"),
+            None,
+            None,
+            helpers::transcoder_vault(),
         ) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+            Ok(_) => panic!("Should panic because fee delay has not passed"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_update_panic_because_caller_restricted() {
+    fn test_fee_adjustment_panic_because_cancelled() {
         let ctx = setup().unwrap();
-
-        match helpers::call_update_agents(
+        let sess = helpers::call_function(
             ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_fee"),
+            Some(vec![String::from("1000")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::cancel_fee"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+        let sess = helpers::update_days(sess, 7);
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::execute_fee"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because proposal was cancelled"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_fee_adjustment_panic_because_caller_restricted() {
+        let ctx = setup().unwrap();
+        match helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.ed, // not bob
+            String::from("IVault::propose_fee"),
+            Some(vec![String::from("1234")]),
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because caller does not have adjust fees role (Bob)"),
+            Err(_) => (),
+        }
+    }
+    #[test]
+    fn test_fee_adjustment_panic_because_overflow() {
+        let ctx = setup().unwrap();
+        match helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_fee"),
+            Some(vec![String::from("2001")]), // above MAX_FEE
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because new fee is too large"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_fee_adjustment_panic_because_step_too_large() {
+        let ctx = setup().unwrap();
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_fee"),
+            Some(vec![String::from("1200")]), // 2% -> 12%
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+        let sess = helpers::update_days(sess, 7);
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::execute_fee"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_fee"),
+            Some(vec![String::from("100")]), // 12% -> 1%
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because fee changes by more than MAX_FEE_STEP"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_withdraw_fees_after_one_second_success() {
+        let ctx = setup().unwrap();
+
+        const STAKE_AMOUNT: u128 = 10_000e10 as u128;
+
+        // default annualized fee of 2%
+        const EXPECTED_FEES: u128 = STAKE_AMOUNT * (helpers::SECOND as u128) / (helpers::YEAR as u128) * 200 / helpers::BIPS;
+
+        // Stake 10k AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, STAKE_AMOUNT).unwrap();
+
+        let sess = helpers::update_in_milliseconds(sess, helpers::SECOND);
+
+        // Verify claimable fees
+        let (shares_before, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(claimable_fees, EXPECTED_FEES);
+
+        // Withdraw fees
+        let sess = helpers::call_withdraw_fees(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        // Verify shares
+        let (shares_after, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        assert_eq!(shares_after - shares_before, EXPECTED_FEES);
+    }
+    #[test]
+    fn test_withdraw_fees_after_one_day_success() {
+        let ctx = setup().unwrap();
+
+        const STAKE_AMOUNT: u128 = 10_000e10 as u128;
+        const EXPECTED_FEES: u128 = STAKE_AMOUNT * (helpers::DAY as u128) / (helpers::YEAR as u128) * 200 / helpers::BIPS;
+
+        // Stake 10k AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, STAKE_AMOUNT).unwrap();
+
+        let sess = helpers::update_days(sess, 1);
+
+        // Verify claimable fees
+        let (shares_before, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(claimable_fees, EXPECTED_FEES);
+
+        // Withdraw fees
+        let sess = helpers::call_withdraw_fees(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        // Verify shares
+        let (shares_after, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        assert_eq!(shares_after - shares_before, EXPECTED_FEES); // annualized fee of 2%
+    }
+    #[test]
+    fn test_withdraw_fees_after_one_year_success() {
+        let ctx = setup().unwrap();
+
+        const STAKE_AMOUNT: u128 = 10_000e10 as u128;
+        const EXPECTED_FEES: u128 = STAKE_AMOUNT * 200 / helpers::BIPS;
+
+        // Stake 10k AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, STAKE_AMOUNT).unwrap();
+
+        // Verify claimable fees
+        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(claimable_fees, 0);
+
+        let sess = helpers::update_in_milliseconds(sess, helpers::YEAR);
+
+        // Verify claimable fees
+        let (shares_before, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(claimable_fees, EXPECTED_FEES);
+
+        // Withdraw fees
+        let sess = helpers::call_withdraw_fees(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        // Verify shares
+        let (shares_after, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        assert_eq!(shares_after - shares_before, EXPECTED_FEES);
+    }
+    #[test]
+    fn test_distribute_fees_to_weighted_recipients() {
+        let ctx = setup().unwrap();
+
+        const STAKE_AMOUNT: u128 = 10_000e10 as u128;
+        const EXPECTED_FEES: u128 = STAKE_AMOUNT * 200 / helpers::BIPS;
+
+        let sess = helpers::call_set_fee_recipients(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            vec![(&ctx.alice, 7000), (&ctx.charlie, 3000)],
+        )
+            .unwrap();
+
+        // Stake 10k AZERO
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, STAKE_AMOUNT).unwrap();
+
+        let sess = helpers::update_in_milliseconds(sess, helpers::YEAR);
+
+        // Anyone can distribute fees
+        let sess = helpers::call_distribute_fees(sess, &ctx.vault, &ctx.dave).unwrap();
+
+        // Verify shares
+        let (alice_shares, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.alice).unwrap();
+        assert_eq!(alice_shares, EXPECTED_FEES * 7000 / helpers::BIPS);
+        let (charlie_shares, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.charlie).unwrap();
+        assert_eq!(charlie_shares, EXPECTED_FEES * 3000 / helpers::BIPS);
+        let (claimable_fees, _sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(claimable_fees, 0);
+    }
+    #[test]
+    fn test_set_fee_recipients_panic_because_invalid_weights() {
+        let ctx = setup().unwrap();
+
+        match helpers::call_set_fee_recipients(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            vec![(&ctx.alice, 7000), (&ctx.charlie, 2000)],
+        ) {
+            Ok(_) => panic!("Should panic because weights do not sum to 100%"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_adjust_fee_delay_panic_because_decrease_timelocked() {
+        let ctx = setup().unwrap();
+
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_fee_delay"),
+            Some(vec![helpers::DAY.to_string()]),
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+
+        // Decrease only applies after the current 7 day delay
+        let sess = helpers::update_days(sess, 6);
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::execute_fee_delay"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because the fee delay decrease is timelocked"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_withdraw_fees_after_adjusted_fee() {
+        const STAKE: u128 = 10_000e10 as u128;
+        const ONE_DAY_FEE_2_PERCENT: u128 = STAKE * helpers::DAY as u128 / helpers::YEAR as u128 * 2_00 / helpers::BIPS;
+        const ONE_DAY_FEE_4_PERCENT: u128 = STAKE * helpers::DAY as u128 / helpers::YEAR as u128 * 4_00 / helpers::BIPS;
+
+        let ctx = setup().unwrap();
+
+        // Decrease the fee delay to 1 day after the current 7 day delay
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_fee_delay"),
+            Some(vec![helpers::DAY.to_string()]),
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+        let sess = helpers::update_days(sess, 7);
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::execute_fee_delay"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+
+        // Initial stake
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, STAKE).unwrap();
+
+        // Propose fee of 4% executable after 1 day
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_fee"),
+            Some(vec![String::from("400")]), // 4% in helpers::BIPS
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+
+        // 2% fee for 1 day
+        let sess = helpers::update_days(sess, 1);
+        let (expected_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(
+            expected_fees, ONE_DAY_FEE_2_PERCENT,
+            "Should have 2% fee for 1 day"
+        );
+
+        // Adjust fee to 4%
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::execute_fee"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+
+        // 4% fee for 1 day
+        let sess = helpers::update_days(sess, 1);
+        let (expected_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(
+            expected_fees,
+            ONE_DAY_FEE_2_PERCENT + ONE_DAY_FEE_4_PERCENT + 599666, // compounding
+            "Should show 2% fee for 1 day and 4% fee for 1 day",
+        );
+
+        let (shares_before, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        let sess = helpers::call_withdraw_fees(sess, &ctx.vault, &ctx.bob).unwrap();
+        let (shares_after, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        assert_eq!(
+            shares_after - shares_before,
+            ONE_DAY_FEE_2_PERCENT + ONE_DAY_FEE_4_PERCENT + 599666, // compounding,
+            "Should withdraw 2% fee for 1 day and 4% fee for 1 day"
+        );
+    }
+    #[test]
+    fn test_withdraw_fees_panic_because_caller_restricted() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000).unwrap();
+        let sess = helpers::update_days(sess, 365);
+        match helpers::call_withdraw_fees(
+            sess,
+            &ctx.vault,
+            &ctx.ed, // not bob
+        ) {
+            Ok(_) => panic!("Should panic because caller does not have the fee to role (Bob)"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_vault_transfer_role_adjust_fee_panic_because_caller_restricted() {
+        let ctx = setup().unwrap();
+        match helpers::call_propose_vault_role(ctx.sess, &ctx.vault, &ctx.alice, "AdjustFee", &ctx.charlie) { // not bob
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_vault_transfer_role_adjust_fee_flow() {
+        let ctx = setup().unwrap();
+
+        let (members, sess) = helpers::get_vault_role_members(ctx.sess, &ctx.vault, "AdjustFee").unwrap();
+        assert_eq!(members, vec![ctx.bob.clone()]);
+
+        // Propose role to Charlie
+        let sess = helpers::call_propose_vault_role(sess, &ctx.vault, &ctx.bob, "AdjustFee", &ctx.charlie).unwrap();
+
+        // Role is unchanged until accepted
+        let (members, sess) = helpers::get_vault_role_members(sess, &ctx.vault, "AdjustFee").unwrap();
+        assert_eq!(members, vec![ctx.bob.clone()]);
+
+        // Charlie accepts the role
+        let sess = helpers::call_accept_vault_role(sess, &ctx.vault, &ctx.charlie, "AdjustFee").unwrap();
+
+        let (members, sess) = helpers::get_vault_role_members(sess, &ctx.vault, "AdjustFee").unwrap();
+        assert_eq!(members, vec![ctx.bob.clone(), ctx.charlie.clone()]);
+
+        // Bob renounces the role leaving Charlie as the only member
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::renounce_role"),
+            Some(vec![String::from("AdjustFee")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        let (members, _sess) = helpers::get_vault_role_members(sess, &ctx.vault, "AdjustFee").unwrap();
+        assert_eq!(members, vec![ctx.charlie.clone()]);
+    }
+    #[test]
+    fn test_vault_accept_role_panic_because_not_proposed() {
+        let ctx = setup().unwrap();
+
+        let sess = helpers::call_propose_vault_role(ctx.sess, &ctx.vault, &ctx.bob, "AdjustFee", &ctx.charlie).unwrap();
+
+        match helpers::call_accept_vault_role(sess, &ctx.vault, &ctx.dave, "AdjustFee") {
+            Ok(_) => panic!("Should panic because caller was not proposed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_vault_accept_role_panic_because_cancelled() {
+        let ctx = setup().unwrap();
+
+        let sess = helpers::call_propose_vault_role(ctx.sess, &ctx.vault, &ctx.bob, "AdjustFee", &ctx.charlie).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::cancel_role"),
+            Some(vec![String::from("AdjustFee"), ctx.charlie.to_string()]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        match helpers::call_accept_vault_role(sess, &ctx.vault, &ctx.charlie, "AdjustFee") {
+            Ok(_) => panic!("Should panic because proposal was cancelled"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_vault_transfer_role_fee_to_panic_because_caller_restricted() {
+        let ctx = setup().unwrap();
+        match helpers::call_propose_vault_role(ctx.sess, &ctx.vault, &ctx.alice, "FeeTo", &ctx.charlie) { // not bob
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_vault_transfer_role_fee_to_flow() {
+        let ctx = setup().unwrap();
+
+        let (members, sess) = helpers::get_vault_role_members(ctx.sess, &ctx.vault, "FeeTo").unwrap();
+        assert_eq!(members, vec![ctx.bob.clone()]);
+
+        // Grant role to Charlie
+        let sess = helpers::call_propose_vault_role(sess, &ctx.vault, &ctx.bob, "FeeTo", &ctx.charlie).unwrap();
+        let sess = helpers::call_accept_vault_role(sess, &ctx.vault, &ctx.charlie, "FeeTo").unwrap();
+
+        // Bob (admin) revokes his own membership
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::revoke_role"),
+            Some(vec![String::from("FeeTo"), ctx.bob.to_string()]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        let (members, _sess) = helpers::get_vault_role_members(sess, &ctx.vault, "FeeTo").unwrap();
+        assert_eq!(members, vec![ctx.charlie.clone()]);
+    }
+    #[test]
+    fn test_vault_set_code_after_delay() {
+        let ctx = setup().unwrap();
+
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::announce_code"),
+            Some(vec![helpers::hash_vault()]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let sess = helpers::update_days(sess, 15);
+        helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::set_code"),
+            Some(vec![helpers::hash_vault()]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+    }
+    #[test]
+    fn test_vault_set_code_panic_because_timelocked() {
+        let ctx = setup().unwrap();
+
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::announce_code"),
+            Some(vec![helpers::hash_vault()]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let sess = helpers::update_days(sess, 14);
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::set_code"),
+            Some(vec![helpers::hash_vault()]),
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because code delay has not passed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_vault_set_code_panic_because_cancelled() {
+        let ctx = setup().unwrap();
+
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::announce_code"),
+            Some(vec![helpers::hash_vault()]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::cancel_code"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let sess = helpers::update_days(sess, 15);
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::set_code"),
+            Some(vec![helpers::hash_vault()]),
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because announcement was cancelled"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_accept_set_code_role_panic_because_disabled() {
+        let ctx = setup().unwrap();
+
+        // Bob proposes Alice for the set code role before disabling upgrades
+        let sess = helpers::call_propose_vault_role(ctx.sess, &ctx.vault, &ctx.bob, "SetCode", &ctx.alice).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::disable_set_code"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        match helpers::call_accept_vault_role(sess, &ctx.vault, &ctx.alice, "SetCode") {
+            Ok(_) => panic!("Should panic because upgrades were disabled"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_vault_code_delay_decrease_panic_because_timelocked() {
+        let ctx = setup().unwrap();
+
+        // Increases apply immediately
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_code_delay"),
+            Some(vec![(helpers::DAY * 20).to_string()]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        // Decreases wait for the current delay
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_code_delay"),
+            Some(vec![(helpers::DAY * 15).to_string()]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::get_code_delay"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let res: Result<u64, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert_eq!(res.unwrap(), helpers::DAY * 20);
+
+        let sess = helpers::update_days(sess, 19);
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::execute_code_delay"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because code delay has not passed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_registry_set_agent_code_panic_because_not_announced() {
+        let ctx = setup().unwrap();
+
+        match helpers::call_function(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            String::from("IRegistry::set_agent_code"),
+            Some(vec![helpers::hash_nominator()]),
+            None,
+            helpers::transcoder_registry(),
+        ) {
+            Ok(_) => panic!("Should panic because upgrade was not announced"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_registry_set_agent_code_panic_because_hash_mismatch() {
+        let ctx = setup().unwrap();
+
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            String::from("IRegistry::announce_code"),
+            Some(vec![String::from("Agent"), helpers::hash_nominator()]),
+            None,
+            helpers::transcoder_registry(),
+        )
+            .unwrap();
+        let sess = helpers::update_days(sess, 15);
+        match helpers::call_function(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            String::from("IRegistry::set_agent_code"),
+            Some(vec![helpers::hash_registry()]),
+            None,
+            helpers::transcoder_registry(),
+        ) {
+            Ok(_) => panic!("Should panic because code hash differs from the announcement"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_registry_adjust_code_delay_panic_because_below_vault_cooldown() {
+        let ctx = setup().unwrap();
+
+        // Vault cooldown grows to 28 days, requiring at least 29 days with the batch interval
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_cooldown"),
+            Some(vec![helpers::DAY.to_string(), String::from("28")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        match helpers::call_function(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            String::from("IRegistry::adjust_code_delay"),
+            Some(vec![(helpers::DAY * 20).to_string()]),
+            None,
+            helpers::transcoder_registry(),
+        ) {
+            Ok(_) => panic!("Should panic because the delay is shorter than the Vault cooldown"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_add_agent_role_flow() {
+        let ctx = setup().unwrap();
+
+        // Check roles
+        let (members, sess) = helpers::get_role_members(ctx.sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
+        assert_eq!(members, vec![ctx.bob.clone()]);
+
+        // Bob (admin) proposes role to Charlie
+        let sess = helpers::propose_role(sess, &ctx.registry, &ctx.bob, &helpers::RoleType::AddAgent, &ctx.charlie).unwrap();
+        // Charlie accepts
+        let sess = helpers::accept_role(sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent).unwrap();
+
+        // Check roles
+        let (members, sess) = helpers::get_role_members(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
+        assert_eq!(members, vec![ctx.bob.clone(), ctx.charlie.clone()]);
+
+        // Bob (admin) revokes the role from Charlie
+        let sess = helpers::revoke_role(sess, &ctx.registry, &ctx.bob, &helpers::RoleType::AddAgent, &ctx.charlie).unwrap();
+
+        let (members, _sess) = helpers::get_role_members(sess, &ctx.registry, &helpers::RoleType::AddAgent).unwrap();
+        assert_eq!(members, vec![ctx.bob.clone()]);
+    }
+    #[test]
+    fn test_nominator_add_agent_role_panic_on_transfer_role_because_caller_not_admin() {
+        let ctx = setup().unwrap();
+
+        // Charlie (not admin) cannot transfer role
+        match helpers::propose_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_add_agent_role_panic_on_revoke_because_caller_not_admin() {
+        let ctx = setup().unwrap();
+
+        // Charlie (not admin) cannot revoke role
+        match helpers::revoke_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.bob) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_admin_role_panic_on_renounce_because_last_admin() {
+        let ctx = setup().unwrap();
+
+        // Bob is the only admin
+        match helpers::renounce_role(ctx.sess, &ctx.registry, &ctx.bob, &helpers::RoleType::Admin) {
+            Ok(_) => panic!("Should panic because the admin role would be left without members"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_add_agent_role_panic_on_accept_because_cancelled() {
+        let ctx = setup().unwrap();
+
+        let sess = helpers::propose_role(ctx.sess, &ctx.registry, &ctx.bob, &helpers::RoleType::AddAgent, &ctx.charlie).unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            String::from("IRegistry::cancel_role"),
+            Some(vec![String::from("AddAgent"), ctx.charlie.to_string()]),
+            None,
+            helpers::transcoder_registry(),
+        )
+            .unwrap();
+
+        match helpers::accept_role(sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent) {
+            Ok(_) => panic!("Should panic because proposal was cancelled"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_agents_role_flow() {
+        let ctx = setup().unwrap();
+
+        // Check roles
+        let (members, sess) = helpers::get_role_members(ctx.sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(members, vec![ctx.bob.clone()]);
+
+        // Bob (admin) proposes role to Charlie
+        let sess = helpers::propose_role(sess, &ctx.registry, &ctx.bob, &helpers::RoleType::UpdateAgents, &ctx.charlie).unwrap();
+        // Charlie accepts
+        let sess = helpers::accept_role(sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::UpdateAgents).unwrap();
+
+        // Check roles
+        let (members, sess) = helpers::get_role_members(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(members, vec![ctx.bob.clone(), ctx.charlie.clone()]);
+
+        // Bob renounces the role
+        let sess = helpers::renounce_role(sess, &ctx.registry, &ctx.bob, &helpers::RoleType::UpdateAgents).unwrap();
+
+        let (members, _sess) = helpers::get_role_members(sess, &ctx.registry, &helpers::RoleType::UpdateAgents).unwrap();
+        assert_eq!(members, vec![ctx.charlie.clone()]);
+    }
+    #[test]
+    fn test_nominator_update_agents_role_panic_on_transfer_role_because_caller_not_admin() {
+        let ctx = setup().unwrap();
+
+        // Charlie (not admin) cannot transfer role
+        match helpers::propose_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::UpdateAgents, &ctx.dave) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_agents_role_panic_on_revoke_because_caller_not_admin() {
+        let ctx = setup().unwrap();
+
+        // Charlie (not admin) cannot revoke role
+        match helpers::revoke_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::UpdateAgents, &ctx.bob) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_agent_role_flow() {
+        let ctx = setup().unwrap();
+
+        // Check roles
+        let (members, sess) = helpers::get_role_members(ctx.sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
+        assert_eq!(members, vec![ctx.bob.clone()]);
+
+        // Bob (admin) proposes role to Charlie
+        let sess = helpers::propose_role(sess, &ctx.registry, &ctx.bob, &helpers::RoleType::RemoveAgent, &ctx.charlie).unwrap();
+        // Charlie accepts
+        let sess = helpers::accept_role(sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::RemoveAgent).unwrap();
+
+        // Check roles
+        let (members, _sess) = helpers::get_role_members(sess, &ctx.registry, &helpers::RoleType::RemoveAgent).unwrap();
+        assert_eq!(members, vec![ctx.bob.clone(), ctx.charlie.clone()]);
+    }
+    #[test]
+    fn test_nominator_remove_agent_role_panic_on_transfer_role_because_caller_not_admin() {
+        let ctx = setup().unwrap();
+
+        // Charlie (not admin) cannot transfer role
+        match helpers::propose_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.dave) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_agent_role_panic_on_revoke_because_caller_not_admin() {
+        let ctx = setup().unwrap();
+
+        // Charlie (not admin) cannot revoke role
+        match helpers::revoke_role(ctx.sess, &ctx.registry, &ctx.charlie, &helpers::RoleType::AddAgent, &ctx.bob) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_addition_panic_because_caller_restricted() {
+        let ctx = setup().unwrap();
+
+        match helpers::call_add_agent(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::AddAgent`
+            &ctx.charlie,
+            &ctx.validators[2],
+            100e12 as u128,
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_update_panic_because_caller_restricted() {
+        let ctx = setup().unwrap();
+
+        match helpers::call_update_agents(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::UpdateAgents`
+            vec![ctx.nominators[0].to_string()],
+            vec![0.to_string()],
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_panic_because_stake_is_non_zero() {
+        let ctx = setup().unwrap();
+
+        // Stake 1k AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+
+        match helpers::call_remove_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob, // has `helpers::RoleType::RemoveAgent`
+            &ctx.nominators[0],
+        ) {
+            Ok(_) => panic!("Should panic because nominators[0] has stake"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_panic_because_caller_restricted() {
+        let ctx = setup().unwrap();
+
+        match helpers::call_remove_agent(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.charlie, // does not have `helpers::RoleType::RemoveAgent`
+            &ctx.nominators[0],
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_nominator_remove_success() {
+        let ctx = setup().unwrap();
+
+        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+            ctx.sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        // Remove agent
+        let sess = helpers::call_remove_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob, // has `helpers::RoleType::RemoveAgent`
+            &ctx.nominators[0],
+        )
+            .unwrap();
+
+        let (total_weight_after, agents_after, _sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len() - 1);
+        assert_eq!(total_weight_after, total_weight_before - agents_before[1].weight);
+        assert_eq!(agents_after[0].address, agents_before[1].address);
+        assert_eq!(agents_after[0].weight, agents_before[1].weight);
+    }
+    #[test]
+    fn test_nominator_addition_equal_weights() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
+
+        // Stake 10 million AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000).unwrap();
+
+        let (stake1, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        let (stake2, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(stake1, 5_000_000); // 50% to agent0
+        assert_eq!(stake2, 5_000_000); // 50% to agent1
+
+        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        // Add nomination agent
+        let (_new_agent, sess) = helpers::call_add_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.bob,
+            &ctx.validators[2],
+            100e12 as u128,
+        )?;
+
+        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len() + 1);
+        assert_eq!(total_weight_after, total_weight_before);
+        assert_eq!(agents_after[2].weight, 0);
+
+        // Update weight to 100
+        let sess = helpers::call_update_agents(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![agents_after[2].address.to_string()],
+            vec![100.to_string()],
+        )
+            .unwrap();
+
+        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len() + 1);
+        assert_eq!(total_weight_after, total_weight_before + 100);
+        assert_eq!(agents_after[2].weight, 100);
+
+        // Stake additional 10 million AZERO
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10000000).unwrap();
+
+        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        let (stake2, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        let (stake3, _, _sess) = helpers::query_nominator_balance(sess, &agents_after[2].address).unwrap();
+        assert_eq!(stake1, 6666668);
+        assert_eq!(stake2, 6666666);
+        assert_eq!(stake3, 6666666);
+
+        Ok(())
+    }
+    #[test]
+    fn test_nominator_addition_unequal_weights() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
+
+        // Stake 10m AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000).unwrap();
+
+        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        let (stake2, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(stake1, 5_000_000); // 50% to agent0
+        assert_eq!(stake2, 5_000_000); // 50% to agent1
+
+        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        // Add nomination agent
+        let (_new_agent, sess) = helpers::call_add_agent(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.bob,
+            &ctx.validators[2],
+            100e12 as u128,
+        )?;
+
+        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(agents_after.len(), agents_before.len() + 1);
+        assert_eq!(total_weight_after, total_weight_before);
+        assert_eq!(agents_after[2].weight, 0);
+
+        // Update weight to 50
+        let sess = helpers::call_update_agents(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![agents_after[2].address.to_string()],
+            vec![50.to_string()],
+        )
+            .unwrap();
+
+        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+            sess,
+            &ctx.registry,
+        )
+            .unwrap();
+
+        assert_eq!(total_weight_after, total_weight_before + 50);
+        assert_eq!(agents_after[2].weight, 50);
+
+        // Stake another 10m AZERO
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000).unwrap();
+
+        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        let (stake2, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        let (stake3, _, _sess) = helpers::query_nominator_balance(sess, &agents_after[2].address).unwrap();
+        assert_eq!(stake1, 5_000_000 + 3_000_000);
+        assert_eq!(stake2, 5_000_000 + 3_000_000);
+        assert_eq!(stake3, 4_000_000);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unlock_weight_change() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
+        let sess = ctx.sess;
+
+        // Stake 5 million AZERO
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1000000).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1000000).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1000000).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1000000).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1000000).unwrap();
+
+        let sess = helpers::update_days(sess, 2);
+
+        // Update agent #1 weight from 100/200 to 50/150
+        let sess = helpers::call_update_agents(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            vec![ctx.nominators[0].to_string()],
+            vec![50.to_string()],
+        )
+            .unwrap();
+
+        // Request unlocks of all 5 million sA0
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1000000).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1000000).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1000000).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1000000).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1000000).unwrap();
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        let sess = helpers::update_days(sess, 14);
+
+        // Fees accumulated for 5m AZERO staked for 2 days
+        let expected_fees = 5_000_000 * 2 / 365 * 200 / helpers::BIPS;
+        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(claimable_fees, expected_fees);
+
+        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        let (stake2, _, _sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(stake1, expected_fees * 50 / 150); // agent 0 fees
+        assert_eq!(stake2, 1 + expected_fees * 100 / 150); // dust and agent 1 fees
+
+        Ok(())
+    }
+    #[test]
+    fn test_withdraw_all() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
+        let sess = ctx.sess;
+
+        // Stake 5 million AZERO
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1000000).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1000000).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1000000).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1000000).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1000000).unwrap();
+
+        // Allow fees to accumulate
+        let sess = helpers::update_days(sess, 2);
+
+        // Update agent weight from 100/200 to 50/150
+        let sess = helpers::call_update_agents(
+            sess,
             &ctx.registry,
-            &ctx.charlie, // does not have `helpers::RoleType::UpdateAgents`
+            &ctx.bob,
             vec![ctx.nominators[0].to_string()],
-            vec![0.to_string()],
+            vec![50.to_string()],
+        )
+            .unwrap();
+
+        // Request unlocking of 5 million AZERO
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1000000).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1000000).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1000000).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1000000).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1000000).unwrap();
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        // Wait for cooldown period
+        let sess = helpers::update_days(sess, 14);
+
+        // Fees accumulated for 5m AZERO staked for 2 days
+        let expected_fees = 5_000_000 * 2 / 365 * 200 / helpers::BIPS;
+        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(claimable_fees, expected_fees);
+
+        // Verify all AZERO is withdrawn except fees
+        let (stake, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(stake, expected_fees * 50 / 150); // agent 0 weight
+        let (stake, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(stake, 1 + expected_fees * 100 / 150); // dust and agent 1 weight
+
+        let fee_split = expected_fees / 5;
+        let (redeemed, sess) = helpers::call_redeem_with_withdraw(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        assert_eq!(redeemed, 1000000 - 1 - fee_split);
+        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.bob, 0).unwrap();
+        assert_eq!(redeemed, 1000000 - 1 - fee_split);
+        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.charlie, 0).unwrap();
+        assert_eq!(redeemed, 1000000 - 1 - fee_split);
+        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.dave, 0).unwrap();
+        assert_eq!(redeemed, 1000000 - 1 - fee_split);
+        let (redeemed, mut sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.ed, 0).unwrap();
+        assert_eq!(redeemed, 1000000 - 1 - fee_split);
+
+        let vault_balance = sess.chain_api().balance(&ctx.vault);
+        assert_eq!(vault_balance, 4, "Vault should only have dust remaining");
+
+        Ok(())
+    }
+    #[test]
+    fn test_token_transfer_from_panics_properly() {
+        let ctx = setup().unwrap();
+
+        // Bob stakes 1m AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000).unwrap();
+
+        // Ed attempts to transfer 1k of Bob's sA0
+        match helpers::call_function(
+            sess,
+            &ctx.share_token,
+            &ctx.ed, // not bob
+            String::from("PSP22::transfer_from"),
+            Some(vec![ctx.bob.to_string(), ctx.ed.to_string(), 1000.to_string(), "[]".to_string()]),
+            None,
+            helpers::transcoder_share_token(),
+        )  {
+            Ok(_) => panic!("Should panic because Bob has not approved Ed to transfer sA0"),
+            Err(res) => println!("{:?}", res.to_string()),
+        };
+    }
+    #[test]
+    fn test_token_transfer_from_works_normally() {
+        let ctx = setup().unwrap();
+
+        // Bob stakes 1m AZERO
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000).unwrap();
+
+        // Bob approves Ed to transfer 1k sA0
+        let sess = helpers::call_function(
+            sess,
+            &ctx.share_token,
+            &ctx.bob,
+            String::from("PSP22::approve"),
+            Some(vec![ctx.ed.to_string(), 1000.to_string()]),
+            None,
+            helpers::transcoder_share_token(),
+        ).unwrap();
+
+        // Ed transfers 1k of Bob's sA0
+        helpers::call_function(
+            sess,
+            &ctx.share_token,
+            &ctx.ed, // previously approved
+            String::from("PSP22::transfer_from"),
+            Some(vec![ctx.bob.to_string(),ctx.ed.to_string(),1000.to_string(),"[]".to_string()]),
+            None,
+            helpers::transcoder_share_token(),
+        ).unwrap();
+    }
+    #[test]
+    fn test_process_batch_panic_because_interval_not_elapsed() {
+        let ctx = setup().unwrap();
+
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1e12 as u128).unwrap();
+
+        match helpers::call_process_batch(sess, &ctx.vault, &ctx.bob) {
+            Ok(_) => panic!("Should panic because batch interval has not elapsed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_process_batch_panic_because_batch_empty() {
+        let ctx = setup().unwrap();
+
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let sess = helpers::update_days(sess, 1);
+
+        match helpers::call_process_batch(sess, &ctx.vault, &ctx.bob) {
+            Ok(_) => panic!("Should panic because batch has no unlock requests"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_batch_unlock_single_unbond_per_agent() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
+
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000e12 as u128).unwrap();
+
+        // Requests are queued without unbonding
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 100e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 100e12 as u128).unwrap();
+        let (_, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(unbonded, 0, "Nominator #1 should not unbond before the batch is processed");
+
+        // Batch is unbonded in a single pass after the batch interval
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.charlie).unwrap();
+        let (staked, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(staked, 900005475401758);
+        assert_eq!(unbonded, 99994524598242);
+        let (staked, unbonded, _sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(staked, 900005475401758);
+        assert_eq!(unbonded, 99994524598242);
+
+        Ok(())
+    }
+    #[test]
+    fn test_redeem_panic_because_batch_not_processed() {
+        let ctx = setup().unwrap();
+
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 100e12 as u128).unwrap();
+        let sess = helpers::update_days(sess, 15);
+
+        match helpers::call_redeem(sess, &ctx.vault, &ctx.alice, 0) {
+            Ok(_) => panic!("Should panic because batch has not been processed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_redeem_pays_unlock_ticket_holder() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
+        const STAKE_AMOUNT: u128 = 1_000e12 as u128;
+        const UNLOCK_AMOUNT: u128 = 100e12 as u128;
+
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, STAKE_AMOUNT).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, UNLOCK_AMOUNT).unwrap();
+
+        // Unlock request is represented by a ticket owned by the requester
+        let (owner, sess) = helpers::query_ticket_owner(sess, &ctx.unlock_ticket, 0).unwrap();
+        assert_eq!(owner, Some(ctx.bob.clone()));
+
+        // Bob sells the pending withdrawal to Alice
+        let sess = helpers::call_transfer_ticket(sess, &ctx.unlock_ticket, &ctx.bob, &ctx.alice, 0).unwrap();
+        let (owner, sess) = helpers::query_ticket_owner(sess, &ctx.unlock_ticket, 0).unwrap();
+        assert_eq!(owner, Some(ctx.alice.clone()));
+
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+        let mut sess = helpers::update_days(sess, 14);
+
+        // AZERO is paid to the ticket holder
+        let alice_balance_before = sess.chain_api().balance(&ctx.alice);
+        let (redeemed, mut sess) = helpers::call_redeem_with_withdraw(sess, &ctx.vault, &ctx.bob, 0).unwrap();
+        assert_eq!(redeemed, 99994524598242); // 100 AZERO less 1 day of fees
+        let alice_balance_after = sess.chain_api().balance(&ctx.alice);
+        assert_eq!(alice_balance_after - alice_balance_before, redeemed);
+
+        // Ticket is burned upon redemption
+        let (owner, _sess) = helpers::query_ticket_owner(sess, &ctx.unlock_ticket, 0).unwrap();
+        assert_eq!(owner, None);
+
+        Ok(())
+    }
+    #[test]
+    fn test_transfer_ticket_panic_because_not_owner() {
+        let ctx = setup().unwrap();
+
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 100e12 as u128).unwrap();
+
+        match helpers::call_transfer_ticket(sess, &ctx.unlock_ticket, &ctx.alice, &ctx.alice, 0) {
+            Ok(_) => panic!("Should panic because Alice does not own the ticket"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_unlock_ids_remain_stable_after_redeem() {
+        let ctx = setup().unwrap();
+
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 100e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 100e12 as u128).unwrap();
+
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+        let sess = helpers::update_days(sess, 14);
+
+        // Redeeming the latest request does not shift earlier ids
+        let (redeemed_1, sess) = helpers::call_redeem_with_withdraw(sess, &ctx.vault, &ctx.bob, 1).unwrap();
+
+        let (requests, sess) = helpers::get_unlock_requests(sess, &ctx.vault, &ctx.bob, 0, 10).unwrap();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].0, 0);
+        assert_eq!(requests[0].1.status, helpers::UnlockStatus::Pending);
+        assert_eq!(requests[1].0, 1);
+        assert_eq!(requests[1].1.status, helpers::UnlockStatus::Redeemed);
+
+        // Pagination
+        let (requests, sess) = helpers::get_unlock_requests(sess, &ctx.vault, &ctx.bob, 1, 10).unwrap();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].0, 1);
+
+        let (redeemed_0, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.bob, 0).unwrap();
+        assert_eq!(redeemed_0, redeemed_1);
+
+        match helpers::call_redeem(sess, &ctx.vault, &ctx.bob, 1) {
+            Ok(_) => panic!("Should panic because request was already redeemed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_cancel_unlock_before_batch_processed() {
+        let ctx = setup().unwrap();
+
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000e12 as u128).unwrap();
+        let (shares, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 100e12 as u128).unwrap();
+        assert_eq!(shares, 900e12 as u128);
+
+        // Original shares are returned
+        let (returned, sess) = helpers::call_cancel_unlock(sess, &ctx.vault, &ctx.bob, 0).unwrap();
+        assert_eq!(returned, 100e12 as u128);
+        let (shares, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        assert_eq!(shares, 1_000e12 as u128);
+
+        // Ticket is burned
+        let (owner, sess) = helpers::query_ticket_owner(sess, &ctx.unlock_ticket, 0).unwrap();
+        assert_eq!(owner, None);
+
+        // Batch no longer has any requests
+        let sess = helpers::update_days(sess, 1);
+        match helpers::call_process_batch(sess, &ctx.vault, &ctx.bob) {
+            Ok(_) => panic!("Should panic because batch is empty"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_cancel_unlock_rebonds_during_cooldown() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
+
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 100e12 as u128).unwrap();
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        let (_, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(unbonded, 49997262299121);
+
+        // Cancel one day into the cooldown
+        let sess = helpers::update_days(sess, 1);
+        let (minted, sess) = helpers::call_cancel_unlock(sess, &ctx.vault, &ctx.bob, 0).unwrap();
+        assert_eq!(minted, 100005475701573); // minted at the current rate
+
+        // Unbonding AZERO is rebonded into the same agents
+        let (staked, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(staked, 500e12 as u128);
+        assert_eq!(unbonded, 0);
+        let (staked, unbonded, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(staked, 500e12 as u128);
+        assert_eq!(unbonded, 0);
+
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, 1_000e12 as u128);
+        let (shares, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.bob).unwrap();
+        assert_eq!(shares, 900e12 as u128 + minted);
+
+        Ok(())
+    }
+    #[test]
+    fn test_cancel_unlock_panic_because_ticket_transferred() {
+        let ctx = setup().unwrap();
+
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 100e12 as u128).unwrap();
+        let sess = helpers::call_transfer_ticket(sess, &ctx.unlock_ticket, &ctx.bob, &ctx.alice, 0).unwrap();
+
+        match helpers::call_cancel_unlock(sess, &ctx.vault, &ctx.bob, 0) {
+            Ok(_) => panic!("Should panic because Bob no longer holds the ticket"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_instant_unlock_from_buffer() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
+        const STAKE_AMOUNT: u128 = 1_000_000e10 as u128;
+        const UNLOCK_AMOUNT: u128 = 100_000e10 as u128;
+
+        // Keep 10% of pooled AZERO idle and charge 0.5% for instant unlocks
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_buffer_target"),
+            Some(vec![String::from("1000")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_instant_unlock_fee"),
+            Some(vec![String::from("50")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, STAKE_AMOUNT).unwrap();
+
+        // Buffer is filled before depositing into agents
+        let (buffer_reserve, sess) = helpers::get_buffer_reserve(sess, &ctx.vault).unwrap();
+        assert_eq!(buffer_reserve, STAKE_AMOUNT / 10);
+        let (staked, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(staked, STAKE_AMOUNT * 9 / 20);
+        let (staked, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(staked, STAKE_AMOUNT * 9 / 20);
+
+        // Instant unlock pays out immediately minus the fee
+        let expected_fee = UNLOCK_AMOUNT * 50 / helpers::BIPS;
+        let (redeemed, sess) = helpers::call_instant_unlock(sess, &ctx.vault, &ctx.alice, UNLOCK_AMOUNT, UNLOCK_AMOUNT - expected_fee).unwrap();
+        assert_eq!(redeemed, UNLOCK_AMOUNT - expected_fee);
+
+        // Fee remains pooled
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, STAKE_AMOUNT - UNLOCK_AMOUNT + expected_fee);
+        let (buffer_reserve, _sess) = helpers::get_buffer_reserve(sess, &ctx.vault).unwrap();
+        assert_eq!(buffer_reserve, expected_fee);
+
+        Ok(())
+    }
+    #[test]
+    fn test_instant_unlock_panic_because_buffer_insufficient() {
+        let ctx = setup().unwrap();
+
+        // No buffer is kept by default
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+
+        match helpers::call_instant_unlock(sess, &ctx.vault, &ctx.alice, 1e12 as u128, 0) {
+            Ok(_) => panic!("Should panic because buffer is empty"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_compound_call() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
+
+        // Stake 1 AZERO
+        let deposit_amount = 1e12 as u128;
+        let (shares, mut sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
+        assert_eq!(shares, deposit_amount); // 1:1 ratio
+
+        // Fund nominator agents to simulate AZERO being claimed
+        let mock_reward = 10_000;
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), mock_reward);
+        sess.chain_api().add_tokens(ctx.nominators[1].clone(), mock_reward);
+
+        // Compound
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::compound"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        let (total_pooled, _sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, deposit_amount + mock_reward * 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compound_charges_performance_fee() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
+
+        // Switch to 2% of compounded rewards
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_fee_mode"),
+            Some(vec![String::from("Performance")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let sess = helpers::update_days(sess, 7);
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::execute_fee_mode"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        // Stake 1 AZERO
+        let deposit_amount = 1e12 as u128;
+        let (_, mut sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
+
+        // Fund nominator agents to simulate AZERO being claimed
+        let mock_reward = 1e10 as u128;
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), mock_reward);
+        sess.chain_api().add_tokens(ctx.nominators[1].clone(), mock_reward);
+
+        // Compound
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::compound"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        // 2% of 0.02 AZERO priced at the redemption ratio excluding the fee
+        let expected_fees = 392310710;
+        let (virtual_shares, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(virtual_shares, expected_fees);
+
+        // Nothing accrues over time
+        let sess = helpers::update_in_milliseconds(sess, helpers::YEAR);
+        let (virtual_shares, _sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
+        assert_eq!(virtual_shares, expected_fees);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fee_mode_adjustment_panic_because_timelocked() {
+        let ctx = setup().unwrap();
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_fee_mode"),
+            Some(vec![String::from("Performance")]),
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+        let sess = helpers::update_days(sess, 6);
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::execute_fee_mode"),
+            None,
+            None,
+            helpers::transcoder_vault(),
         ) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+            Ok(_) => panic!("Should panic because fee delay has not passed"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_remove_panic_because_stake_is_non_zero() {
+    fn test_compound_before_initial_stake() -> Result<(), Box<dyn Error>> {
         let ctx = setup().unwrap();
+        let mut sess = ctx.sess;
 
-        // Stake 1k AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        // Send funds to an agent
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), 1000);
 
-        match helpers::call_remove_agent(
+        // Compound
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::compound"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, 1000);
+
+        let deposit_amount = 1e12 as u128;
+        let (shares, _sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
+        assert_eq!(shares, deposit_amount); // 1:1 ratio
+
+        Ok(())
+    }
+    #[test]
+    fn test_multisig_adjust_fee() {
+        let ctx = setup().unwrap();
+        let (multisig, sess) = helpers::deploy_multisig(ctx.sess, vec![&ctx.bob, &ctx.alice, &ctx.charlie], 2).unwrap();
+        let owners = (&ctx.bob, &ctx.alice);
+
+        // Hand the adjust fee role to the multisig
+        let sess = helpers::call_propose_vault_role(sess, &ctx.vault, &ctx.bob, "AdjustFee", &multisig).unwrap();
+        let sess = helpers::call_through_multisig(
+            sess,
+            &multisig,
+            owners,
+            &ctx.vault,
+            helpers::transcoder_vault(),
+            "IVault::accept_role",
+            vec![String::from("AdjustFee")],
+        ).unwrap();
+
+        let sess = helpers::call_through_multisig(
+            sess,
+            &multisig,
+            owners,
+            &ctx.vault,
+            helpers::transcoder_vault(),
+            "IVault::propose_fee",
+            vec![String::from("1000")],
+        ).unwrap();
+        let sess = helpers::update_days(sess, 7);
+        let sess = helpers::call_through_multisig(
+            sess,
+            &multisig,
+            owners,
+            &ctx.vault,
+            helpers::transcoder_vault(),
+            "IVault::execute_fee",
+            vec![],
+        ).unwrap();
+
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::get_fee_percentage"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+        let res: Result<u16, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert_eq!(res.unwrap(), 1000);
+    }
+    #[test]
+    fn test_multisig_update_agents() {
+        let ctx = setup().unwrap();
+        let (multisig, sess) = helpers::deploy_multisig(ctx.sess, vec![&ctx.bob, &ctx.alice, &ctx.charlie], 2).unwrap();
+        let owners = (&ctx.alice, &ctx.charlie);
+
+        // Hand the update agents role to the multisig
+        let sess = helpers::propose_role(sess, &ctx.registry, &ctx.bob, &helpers::RoleType::UpdateAgents, &multisig).unwrap();
+        let sess = helpers::call_through_multisig(
             sess,
+            &multisig,
+            owners,
             &ctx.registry,
-            &ctx.bob, // has `helpers::RoleType::RemoveAgent`
-            &ctx.nominators[0],
-        ) {
-            Ok(_) => panic!("Should panic because nominators[0] has stake"),
+            helpers::transcoder_registry(),
+            "IRegistry::accept_role",
+            vec![String::from("UpdateAgents")],
+        ).unwrap();
+
+        let sess = helpers::call_through_multisig(
+            sess,
+            &multisig,
+            owners,
+            &ctx.registry,
+            helpers::transcoder_registry(),
+            "IRegistry::update_agents",
+            vec![
+                serde_json::to_string(&ctx.nominators.iter().map(|n| n.to_string()).collect::<Vec<String>>()).unwrap(),
+                serde_json::to_string(&vec!["50", "150"]).unwrap(),
+            ],
+        ).unwrap();
+
+        let (total_weight, agents, _sess) = helpers::get_agents(sess, &ctx.registry).unwrap();
+        assert_eq!(total_weight, 200);
+        assert_eq!(agents[0].weight, 50);
+        assert_eq!(agents[1].weight, 150);
+    }
+    #[test]
+    fn test_multisig_set_code() {
+        let ctx = setup().unwrap();
+        let (multisig, sess) = helpers::deploy_multisig(ctx.sess, vec![&ctx.bob, &ctx.alice, &ctx.charlie], 2).unwrap();
+        let owners = (&ctx.charlie, &ctx.bob);
+
+        // Hand the set code role to the multisig
+        let sess = helpers::call_propose_vault_role(sess, &ctx.vault, &ctx.bob, "SetCode", &multisig).unwrap();
+        let sess = helpers::call_through_multisig(
+            sess,
+            &multisig,
+            owners,
+            &ctx.vault,
+            helpers::transcoder_vault(),
+            "IVault::accept_role",
+            vec![String::from("SetCode")],
+        ).unwrap();
+
+        let sess = helpers::call_through_multisig(
+            sess,
+            &multisig,
+            owners,
+            &ctx.vault,
+            helpers::transcoder_vault(),
+            "IVault::announce_code",
+            vec![helpers::hash_vault()],
+        ).unwrap();
+        let sess = helpers::update_days(sess, 15);
+        let sess = helpers::call_through_multisig(
+            sess,
+            &multisig,
+            owners,
+            &ctx.vault,
+            helpers::transcoder_vault(),
+            "IVault::set_code",
+            vec![helpers::hash_vault()],
+        ).unwrap();
+
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::get_code_announcement"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+        .unwrap();
+        let announcement: Result<Option<([u8; 32], u64)>, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert!(announcement.unwrap().is_none());
+    }
+    #[test]
+    fn test_multisig_execute_panic_because_threshold_not_reached() {
+        let ctx = setup().unwrap();
+        let (multisig, sess) = helpers::deploy_multisig(ctx.sess, vec![&ctx.bob, &ctx.alice, &ctx.charlie], 2).unwrap();
+
+        let (id, sess) = helpers::call_multisig_propose(
+            sess,
+            &multisig,
+            &ctx.bob,
+            &ctx.vault,
+            helpers::transcoder_vault(),
+            "IVault::propose_fee",
+            vec![String::from("1000")],
+        ).unwrap();
+
+        match helpers::call_multisig_execute(sess, &multisig, &ctx.bob, id) {
+            Ok(_) => panic!("Should panic because only one owner confirmed"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_remove_panic_because_caller_restricted() {
+    fn test_multisig_execute_panic_because_call_failed() {
         let ctx = setup().unwrap();
+        let (multisig, sess) = helpers::deploy_multisig(ctx.sess, vec![&ctx.bob, &ctx.alice, &ctx.charlie], 2).unwrap();
 
-        match helpers::call_remove_agent(
-            ctx.sess,
-            &ctx.registry,
-            &ctx.charlie, // does not have `helpers::RoleType::RemoveAgent`
-            &ctx.nominators[0],
+        // Multisig does not have the adjust fee role
+        let (id, sess) = helpers::call_multisig_propose(
+            sess,
+            &multisig,
+            &ctx.bob,
+            &ctx.vault,
+            helpers::transcoder_vault(),
+            "IVault::propose_fee",
+            vec![String::from("1000")],
+        ).unwrap();
+        let sess = helpers::call_multisig_confirm(sess, &multisig, &ctx.alice, id).unwrap();
+
+        match helpers::call_multisig_execute(sess, &multisig, &ctx.bob, id) {
+            Ok(_) => panic!("Should panic because the multisig lacks the adjust fee role"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_multisig_propose_panic_because_caller_not_owner() {
+        let ctx = setup().unwrap();
+        let (multisig, sess) = helpers::deploy_multisig(ctx.sess, vec![&ctx.bob, &ctx.alice, &ctx.charlie], 2).unwrap();
+
+        match helpers::call_multisig_propose(
+            sess,
+            &multisig,
+            &ctx.dave,
+            &ctx.vault,
+            helpers::transcoder_vault(),
+            "IVault::propose_fee",
+            vec![String::from("1000")],
         ) {
-            Ok(_) => panic!("Should panic because caller is restricted"),
+            Ok(_) => panic!("Should panic because caller is not an owner"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_report_slash_socializes_loss() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000e12 as u128).unwrap();
+
+        let (staked, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(staked, 1_000e12 as u128);
+
+        // Validator of the first agent is slashed by 100 AZERO
+        let sess = helpers::call_report_slash(sess, &ctx.vault, &ctx.bob, &ctx.nominators[0], 900e12 as u128, 0).unwrap();
+
+        let (staked, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(staked, 900e12 as u128);
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, 1_900e12 as u128);
+
+        // Both stakers carry half of the loss
+        let (alice_shares, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.alice).unwrap();
+        let (charlie_shares, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.charlie).unwrap();
+        let (alice_azero, sess) = helpers::get_azero_from_shares(sess, &ctx.vault, alice_shares).unwrap();
+        let (charlie_azero, _sess) = helpers::get_azero_from_shares(sess, &ctx.vault, charlie_shares).unwrap();
+        assert_eq!(alice_azero, 950e12 as u128);
+        assert_eq!(charlie_azero, 950e12 as u128);
+    }
+    #[test]
+    fn test_report_slash_panic_because_caller_restricted() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+
+        match helpers::call_report_slash(sess, &ctx.vault, &ctx.alice, &ctx.nominators[0], 400e12 as u128, 0) { // not bob
+            Ok(_) => panic!("Should panic because caller does not have the slash reporter role (Bob)"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_report_slash_panic_because_stake_not_lower() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+
+        match helpers::call_report_slash(sess, &ctx.vault, &ctx.bob, &ctx.nominators[0], 500e12 as u128, 0) {
+            Ok(_) => panic!("Should panic because neither the reported stake nor unbonding is lower"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_report_slash_panic_because_agent_not_found() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+
+        match helpers::call_report_slash(sess, &ctx.vault, &ctx.bob, &ctx.validators[0], 0, 0) {
+            Ok(_) => panic!("Should panic because the account is not an agent"),
             Err(_) => (),
         };
     }
     #[test]
-    fn test_nominator_remove_success() {
+    fn test_report_slash_haircuts_unbonding_requests() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 200e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 200e12 as u128).unwrap();
+
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        let (staked, unbonding, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(staked, 800e12 as u128);
+        assert_eq!(unbonding, 200e12 as u128);
+
+        // Validator of the first agent is slashed by 100 AZERO of unbonding funds
+        let sess = helpers::call_report_slash(sess, &ctx.vault, &ctx.bob, &ctx.nominators[0], 800e12 as u128, 100e12 as u128).unwrap();
+
+        // Remaining stakers are unaffected
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, 1_600e12 as u128);
+
+        // Both pending requests of the batch carry half of the loss
+        let (alice_azero, sess) = helpers::get_claimable_azero(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        let (charlie_azero, sess) = helpers::get_claimable_azero(sess, &ctx.vault, &ctx.charlie, 0).unwrap();
+        assert_eq!(alice_azero, Some(150e12 as u128));
+        assert_eq!(charlie_azero, Some(150e12 as u128));
+
+        let (claimable_time, mut sess) = helpers::get_claimable_time(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        sess.chain_api().set_timestamp(claimable_time.unwrap());
+        let (redeemed, sess) = helpers::call_redeem_with_withdraw(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        assert_eq!(redeemed, 150e12 as u128);
+        let (redeemed, _sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.charlie, 0).unwrap();
+        assert_eq!(redeemed, 150e12 as u128);
+    }
+    #[test]
+    fn test_report_slash_haircuts_uneven_batches() {
         let ctx = setup().unwrap();
 
-        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+        // Disable fees to keep a 1:1 redemption ratio across batches
+        let sess = helpers::call_function(
             ctx.sess,
-            &ctx.registry,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::propose_fee"),
+            Some(vec![String::from("0")]),
+            None,
+            helpers::transcoder_vault(),
         )
             .unwrap();
-
-        // Remove agent
-        let sess = helpers::call_remove_agent(
+        let sess = helpers::update_days(sess, 7);
+        let sess = helpers::call_function(
             sess,
-            &ctx.registry,
-            &ctx.bob, // has `helpers::RoleType::RemoveAgent`
-            &ctx.nominators[0],
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::execute_fee"),
+            None,
+            None,
+            helpers::transcoder_vault(),
         )
             .unwrap();
 
-        let (total_weight_after, agents_after, _sess) = helpers::get_agents(
-            sess,
-            &ctx.registry,
-        )
-            .unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1_000e12 as u128).unwrap();
 
-        assert_eq!(agents_after.len(), agents_before.len() - 1);
-        assert_eq!(total_weight_after, total_weight_before - agents_before[1].weight);
-        assert_eq!(agents_after[0].address, agents_before[1].address);
-        assert_eq!(agents_after[0].weight, agents_before[1].weight);
+        // Three batches unbonding 300, 300 and 100 AZERO from the first agent
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 600e12 as u128).unwrap();
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 600e12 as u128).unwrap();
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 200e12 as u128).unwrap();
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+
+        let (staked, unbonding, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(staked, 300e12 as u128);
+        assert_eq!(unbonding, 700e12 as u128);
+
+        // All but 1 of the unbonding funds are slashed, rounding dust exceeds what the oldest batch unbonded
+        let sess = helpers::call_report_slash(sess, &ctx.vault, &ctx.bob, &ctx.nominators[0], 300e12 as u128, 1).unwrap();
+
+        // Haircuts are capped at the AZERO each batch unbonded from the agent
+        let (azero, sess) = helpers::get_claimable_azero(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        assert_eq!(azero, Some(300e12 as u128));
+        let (azero, sess) = helpers::get_claimable_azero(sess, &ctx.vault, &ctx.charlie, 0).unwrap();
+        assert_eq!(azero, Some(300e12 as u128 + 1));
+        let (azero, sess) = helpers::get_claimable_azero(sess, &ctx.vault, &ctx.alice, 1).unwrap();
+        assert_eq!(azero, Some(100e12 as u128 + 1));
+
+        // The remainder is socialized
+        let (total_pooled, _sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, 600e12 as u128 - 1);
     }
     #[test]
-    fn test_nominator_addition_equal_weights() -> Result<(), Box<dyn Error>> {
+    fn test_get_claimable_azero_unprocessed_batch() {
         let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 200e12 as u128).unwrap();
 
-        // Stake 10 million AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000).unwrap();
+        // Unprocessed requests are valued at the current share price
+        let (azero, sess) = helpers::get_claimable_azero(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        assert_eq!(azero, Some(200e12 as u128));
 
-        let (stake1, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        let (stake2, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        assert_eq!(stake1, 5_000_000); // 50% to agent0
-        assert_eq!(stake2, 5_000_000); // 50% to agent1
+        // Unknown requests are not claimable
+        let (azero, _sess) = helpers::get_claimable_azero(sess, &ctx.vault, &ctx.alice, 1).unwrap();
+        assert_eq!(azero, None);
+    }
+    #[test]
+    fn test_rebalance_moves_stake_to_under_allocated_agents() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 2_000e12 as u128).unwrap();
 
-        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+        // First agent is de-weighted
+        let sess = helpers::call_update_agents(
             sess,
             &ctx.registry,
+            &ctx.bob,
+            vec![ctx.nominators[0].to_string(), ctx.nominators[1].to_string()],
+            vec![String::from("50"), String::from("150")],
         )
             .unwrap();
 
-        // Add nomination agent
-        let (_new_agent, sess) = helpers::call_add_agent(
-            sess,
-            &ctx.registry,
-            &ctx.bob,
-            &ctx.bob,
-            &ctx.validators[2],
-            100e12 as u128,
-        )?;
+        let sess = helpers::call_rebalance(sess, &ctx.vault, &ctx.bob, 1_000e12 as u128).unwrap();
 
-        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+        // Only the over-allocation is unbonded
+        let (staked, unbonding, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(staked, 500e12 as u128);
+        assert_eq!(unbonding, 500e12 as u128);
+
+        // Redemption rate is unaffected while rebalancing
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, 2_000e12 as u128);
+        let (alice_shares, sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.alice).unwrap();
+        let (alice_azero, sess) = helpers::get_azero_from_shares(sess, &ctx.vault, alice_shares).unwrap();
+        assert_eq!(alice_azero, 2_000e12 as u128);
+
+        // Anyone can complete the rebalance after the cooldown
+        let sess = helpers::update_days(sess, 14);
+        let sess = helpers::call_complete_rebalance(sess, &ctx.vault, &ctx.alice).unwrap();
+
+        let (staked, unbonding, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
+        assert_eq!(staked, 500e12 as u128);
+        assert_eq!(unbonding, 0);
+        let (staked, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
+        assert_eq!(staked, 1_500e12 as u128);
+        let (total_pooled, _sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, 2_000e12 as u128);
+    }
+    #[test]
+    fn test_redeem_while_rebalance_in_flight() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 2_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 200e12 as u128).unwrap();
+
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
+        let sess = helpers::update_days(sess, 14);
+
+        // First agent is de-weighted and a rebalance begins unbonding
+        let sess = helpers::call_update_agents(
             sess,
             &ctx.registry,
+            &ctx.bob,
+            vec![ctx.nominators[0].to_string(), ctx.nominators[1].to_string()],
+            vec![String::from("50"), String::from("150")],
         )
             .unwrap();
+        let sess = helpers::call_rebalance(sess, &ctx.vault, &ctx.bob, 1_000e12 as u128).unwrap();
 
-        assert_eq!(agents_after.len(), agents_before.len() + 1);
-        assert_eq!(total_weight_after, total_weight_before);
-        assert_eq!(agents_after[2].weight, 0);
+        // Matured request is redeemable while the rebalance is still unbonding
+        let (claimable, sess) = helpers::get_claimable_azero(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        let (redeemed, sess) = helpers::call_redeem_with_withdraw(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        assert_eq!(Some(redeemed), claimable);
+
+        // The rebalance still completes after its own cooldown
+        let sess = helpers::update_days(sess, 14);
+        let _sess = helpers::call_complete_rebalance(sess, &ctx.vault, &ctx.alice).unwrap();
+    }
+    #[test]
+    fn test_process_batch_panic_because_rebalance_in_flight() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 2_000e12 as u128).unwrap();
 
-        // Update weight to 100
         let sess = helpers::call_update_agents(
             sess,
             &ctx.registry,
             &ctx.bob,
-            vec![agents_after[2].address.to_string()],
-            vec![100.to_string()],
+            vec![ctx.nominators[0].to_string(), ctx.nominators[1].to_string()],
+            vec![String::from("50"), String::from("150")],
         )
             .unwrap();
+        let sess = helpers::call_rebalance(sess, &ctx.vault, &ctx.bob, 500e12 as u128).unwrap();
 
-        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+        // Only 1500 AZERO remain bonded
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_600e12 as u128).unwrap();
+        let sess = helpers::update_days(sess, 1);
+        match helpers::call_process_batch(sess, &ctx.vault, &ctx.bob) {
+            Ok(_) => panic!("Should panic because the batch needs AZERO being rebalanced"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_rebalance_panic_because_caller_restricted() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 2_000e12 as u128).unwrap();
+
+        match helpers::call_rebalance(sess, &ctx.vault, &ctx.alice, 1_000e12 as u128) { // not bob
+            Ok(_) => panic!("Should panic because caller does not have the rebalancer role (Bob)"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_rebalance_panic_because_balanced() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 2_000e12 as u128).unwrap();
+
+        match helpers::call_rebalance(sess, &ctx.vault, &ctx.bob, 1_000e12 as u128) {
+            Ok(_) => panic!("Should panic because no agent is over-allocated"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_complete_rebalance_panic_because_cooldown() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 2_000e12 as u128).unwrap();
+        let sess = helpers::call_update_agents(
             sess,
             &ctx.registry,
+            &ctx.bob,
+            vec![ctx.nominators[0].to_string(), ctx.nominators[1].to_string()],
+            vec![String::from("50"), String::from("150")],
         )
             .unwrap();
+        let sess = helpers::call_rebalance(sess, &ctx.vault, &ctx.bob, 1_000e12 as u128).unwrap();
 
-        assert_eq!(agents_after.len(), agents_before.len() + 1);
-        assert_eq!(total_weight_after, total_weight_before + 100);
-        assert_eq!(agents_after[2].weight, 100);
-
-        // Stake additional 10 million AZERO
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10000000).unwrap();
-
-        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        let (stake2, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        let (stake3, _, _sess) = helpers::query_nominator_balance(sess, &agents_after[2].address).unwrap();
-        assert_eq!(stake1, 6666668);
-        assert_eq!(stake2, 6666666);
-        assert_eq!(stake3, 6666666);
-
-        Ok(())
+        let sess = helpers::update_days(sess, 13);
+        match helpers::call_complete_rebalance(sess, &ctx.vault, &ctx.alice) {
+            Ok(_) => panic!("Should panic because unbonding has not completed"),
+            Err(_) => (),
+        };
     }
     #[test]
-    fn test_nominator_addition_unequal_weights() -> Result<(), Box<dyn Error>> {
+    fn test_compound_range_spreads_agents_across_calls() -> Result<(), Box<dyn Error>> {
         let ctx = setup().unwrap();
 
-        // Stake 10m AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000).unwrap();
+        // Stake 1 AZERO
+        let deposit_amount = 1e12 as u128;
+        let (_, mut sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
 
-        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        let (stake2, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        assert_eq!(stake1, 5_000_000); // 50% to agent0
-        assert_eq!(stake2, 5_000_000); // 50% to agent1
+        // Fund nominator agents to simulate AZERO being claimed
+        let mock_reward = 10_000;
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), mock_reward);
+        sess.chain_api().add_tokens(ctx.nominators[1].clone(), mock_reward);
 
-        let (total_weight_before, agents_before, sess) = helpers::get_agents(
+        // Compound the first agent only
+        let sess = helpers::call_function(
             sess,
-            &ctx.registry,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::compound_range"),
+            Some(vec![String::from("0"), String::from("1")]),
+            None,
+            helpers::transcoder_vault(),
         )
             .unwrap();
 
-        // Add nomination agent
-        let (_new_agent, sess) = helpers::call_add_agent(
-            sess,
-            &ctx.registry,
-            &ctx.bob,
-            &ctx.bob,
-            &ctx.validators[2],
-            100e12 as u128,
-        )?;
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, deposit_amount + mock_reward);
 
-        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+        let sess = helpers::call_function(
             sess,
-            &ctx.registry,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::get_compound_cursor"),
+            None,
+            None,
+            helpers::transcoder_vault(),
         )
             .unwrap();
+        let cursor: Result<u64, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert_eq!(cursor.unwrap(), 1);
 
-        assert_eq!(agents_after.len(), agents_before.len() + 1);
-        assert_eq!(total_weight_after, total_weight_before);
-        assert_eq!(agents_after[2].weight, 0);
-
-        // Update weight to 50
-        let sess = helpers::call_update_agents(
+        // Compound the remaining agent, wrapping the cursor
+        let sess = helpers::call_function(
             sess,
-            &ctx.registry,
+            &ctx.vault,
             &ctx.bob,
-            vec![agents_after[2].address.to_string()],
-            vec![50.to_string()],
+            String::from("IVault::compound_range"),
+            Some(vec![String::from("1"), String::from("10")]),
+            None,
+            helpers::transcoder_vault(),
         )
             .unwrap();
 
-        let (total_weight_after, agents_after, sess) = helpers::get_agents(
+        let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
+        assert_eq!(total_pooled, deposit_amount + mock_reward * 2);
+
+        let sess = helpers::call_function(
             sess,
-            &ctx.registry,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::get_compound_cursor"),
+            None,
+            None,
+            helpers::transcoder_vault(),
         )
             .unwrap();
-
-        assert_eq!(total_weight_after, total_weight_before + 50);
-        assert_eq!(agents_after[2].weight, 50);
-
-        // Stake another 10m AZERO
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 10_000_000).unwrap();
-
-        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        let (stake2, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        let (stake3, _, _sess) = helpers::query_nominator_balance(sess, &agents_after[2].address).unwrap();
-        assert_eq!(stake1, 5_000_000 + 3_000_000);
-        assert_eq!(stake2, 5_000_000 + 3_000_000);
-        assert_eq!(stake3, 4_000_000);
+        let cursor: Result<u64, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert_eq!(cursor.unwrap(), 0);
 
         Ok(())
     }
-
     #[test]
-    fn test_unlock_weight_change() -> Result<(), Box<dyn Error>> {
+    fn test_withdraw_unbonded_range_allows_redeem() -> Result<(), Box<dyn Error>> {
         let ctx = setup().unwrap();
-        let sess = ctx.sess;
-
-        // Stake 5 million AZERO
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1000000).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1000000).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1000000).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1000000).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1000000).unwrap();
-
-        let sess = helpers::update_days(sess, 2);
-
-        // Update agent #1 weight from 100/200 to 50/150
-        let sess = helpers::call_update_agents(
-            sess,
-            &ctx.registry,
-            &ctx.bob,
-            vec![ctx.nominators[0].to_string()],
-            vec![50.to_string()],
-        )
-            .unwrap();
-
-        // Request unlocks of all 5 million sA0
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1000000).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1000000).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1000000).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1000000).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1000000).unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1_000e12 as u128).unwrap();
+        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 100e12 as u128).unwrap();
 
+        let sess = helpers::update_days(sess, 1);
+        let sess = helpers::call_process_batch(sess, &ctx.vault, &ctx.bob).unwrap();
         let sess = helpers::update_days(sess, 14);
 
-        // Fees accumulated for 5m AZERO staked for 2 days
-        let expected_fees = 5_000_000 * 2 / 365 * 200 / helpers::BIPS;
-        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
-        assert_eq!(claimable_fees, expected_fees);
+        // Withdraw from each agent in a separate transaction
+        let mut sess = sess;
+        for start in 0..2 {
+            sess = helpers::call_function(
+                sess,
+                &ctx.vault,
+                &ctx.charlie,
+                String::from("IVault::withdraw_unbonded_range"),
+                Some(vec![start.to_string(), String::from("1")]),
+                None,
+                helpers::transcoder_vault(),
+            )
+                .unwrap();
+        }
 
-        let (stake1, _, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        let (stake2, _, _sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        assert_eq!(stake1, expected_fees * 50 / 150); // agent 0 fees
-        assert_eq!(stake2, 1 + expected_fees * 100 / 150); // dust and agent 1 fees
+        let (redeemed, _sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.alice, 0).unwrap();
+        assert_eq!(redeemed, 100e12 as u128);
 
         Ok(())
     }
     #[test]
-    fn test_withdraw_all() -> Result<(), Box<dyn Error>> {
+    fn test_compound_range_panic_because_invalid_range() {
         let ctx = setup().unwrap();
-        let sess = ctx.sess;
-
-        // Stake 5 million AZERO
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1000000).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1000000).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1000000).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1000000).unwrap();
-        let (_, sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1000000).unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1e12 as u128).unwrap();
 
-        // Allow fees to accumulate
-        let sess = helpers::update_days(sess, 2);
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::compound_range"),
+            Some(vec![String::from("2"), String::from("1")]),
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because the range starts beyond the last agent"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_compound_pays_bounty_to_caller() -> Result<(), Box<dyn Error>> {
+        let ctx = setup().unwrap();
 
-        // Update agent weight from 100/200 to 50/150
-        let sess = helpers::call_update_agents(
+        // 1% of compounded AZERO paid at most once per day, increases wait for the fee delay
+        let sess = helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_compound_bounty"),
+            Some(vec![String::from("100"), (100e12 as u128).to_string(), helpers::DAY.to_string()]),
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let sess = helpers::update_days(sess, 7);
+        let sess = helpers::call_function(
             sess,
-            &ctx.registry,
+            &ctx.vault,
             &ctx.bob,
-            vec![ctx.nominators[0].to_string()],
-            vec![50.to_string()],
+            String::from("IVault::execute_compound_bounty"),
+            None,
+            None,
+            helpers::transcoder_vault(),
         )
             .unwrap();
 
-        // Request unlocking of 5 million AZERO
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.alice, 1000000).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1000000).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.charlie, 1000000).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.dave, 1000000).unwrap();
-        let (_, sess) = helpers::call_request_unlock(sess, &ctx.vault, &ctx.share_token, &ctx.ed, 1000000).unwrap();
-
-        // Wait for cooldown period
-        let sess = helpers::update_days(sess, 14);
+        // Stake 1 AZERO
+        let deposit_amount = 1e12 as u128;
+        let (_, mut sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
 
-        // Fees accumulated for 5m AZERO staked for 2 days
-        let expected_fees = 5_000_000 * 2 / 365 * 200 / helpers::BIPS;
-        let (claimable_fees, sess) = helpers::get_current_virtual_shares(sess, &ctx.vault).unwrap();
-        assert_eq!(claimable_fees, expected_fees);
+        // Fund nominator agents to simulate AZERO being claimed
+        let mock_reward = 1e10 as u128;
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), mock_reward);
+        sess.chain_api().add_tokens(ctx.nominators[1].clone(), mock_reward);
 
-        // Verify all AZERO is withdrawn except fees
-        let (stake, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[0]).unwrap();
-        assert_eq!(stake, expected_fees * 50 / 150); // agent 0 weight
-        let (stake, _unbond, sess) = helpers::query_nominator_balance(sess, &ctx.nominators[1]).unwrap();
-        assert_eq!(stake, 1 + expected_fees * 100 / 150); // dust and agent 1 weight
+        let mut sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.charlie,
+            String::from("IVault::compound"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
 
-        let fee_split = expected_fees / 5;
-        let (redeemed, sess) = helpers::call_redeem_with_withdraw(sess, &ctx.vault, &ctx.alice, 0).unwrap();
-        assert_eq!(redeemed, 1000000 + 32 - fee_split - 10);
-        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.bob, 0).unwrap();
-        assert_eq!(redeemed, 1000000 + 32 - fee_split - 9);
-        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.charlie, 0).unwrap();
-        assert_eq!(redeemed, 1000000 + 32 - fee_split - 8);
-        let (redeemed, sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.dave, 0).unwrap();
-        assert_eq!(redeemed, 1000000 + 32 - fee_split - 8);
-        let (redeemed, mut sess) = helpers::call_redeem(sess, &ctx.vault, &ctx.ed, 0).unwrap();
-        assert_eq!(redeemed, 1000000 + 32 - fee_split - 8);
+        // Bounty of 2e8 AZERO priced excluding the bounty itself
+        let bounty = 2e8 as u128;
+        let expected_shares = bounty * deposit_amount / (deposit_amount + 2 * mock_reward - bounty);
+        let (charlie_shares, sess_) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.charlie).unwrap();
+        assert_eq!(charlie_shares, expected_shares);
+        sess = sess_;
 
-        let vault_balance = sess.chain_api().balance(&ctx.vault);
-        assert_eq!(vault_balance, 1, "Vault should only have dust remaining");
+        // No bounty is paid within the interval
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), mock_reward);
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.charlie,
+            String::from("IVault::compound"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let (charlie_shares, _sess) = helpers::query_token_balance(sess, &ctx.share_token, &ctx.charlie).unwrap();
+        assert_eq!(charlie_shares, expected_shares);
 
         Ok(())
     }
     #[test]
-    fn test_token_transfer_from_panics_properly() {
+    fn test_adjust_compound_bounty_panic_because_caller_restricted() {
         let ctx = setup().unwrap();
 
-        // Bob stakes 1m AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000).unwrap();
+        match helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.alice,
+            String::from("IVault::adjust_compound_bounty"),
+            Some(vec![String::from("100"), (100e12 as u128).to_string(), helpers::DAY.to_string()]),
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because caller is restricted"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_adjust_compound_bounty_panic_because_invalid_percent() {
+        let ctx = setup().unwrap();
 
-        // Ed attempts to transfer 1k of Bob's sA0
         match helpers::call_function(
-            sess,
-            &ctx.share_token,
-            &ctx.ed, // not bob
-            String::from("PSP22::transfer_from"),
-            Some(vec![ctx.bob.to_string(), ctx.ed.to_string(), 1000.to_string(), "[]".to_string()]),
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_compound_bounty"),
+            Some(vec![String::from("101"), (100e12 as u128).to_string(), helpers::DAY.to_string()]),
             None,
-            helpers::transcoder_share_token(),
-        )  {
-            Ok(_) => panic!("Should panic because Bob has not approved Ed to transfer sA0"),
-            Err(res) => println!("{:?}", res.to_string()),
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because bounty exceeds 1%"),
+            Err(_) => (),
         };
     }
     #[test]
-    fn test_token_transfer_from_works_normally() {
+    fn test_adjust_compound_bounty_panic_because_max_exceeded() {
         let ctx = setup().unwrap();
 
-        // Bob stakes 1m AZERO
-        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1_000_000).unwrap();
+        match helpers::call_function(
+            ctx.sess,
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::adjust_compound_bounty"),
+            Some(vec![String::from("100"), u128::MAX.to_string(), helpers::DAY.to_string()]),
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because the maximum bounty exceeds 100 AZERO"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_adjust_compound_bounty_panic_because_timelocked() {
+        let ctx = setup().unwrap();
 
-        // Bob approves Ed to transfer 1k sA0
         let sess = helpers::call_function(
-            sess,
-            &ctx.share_token,
+            ctx.sess,
+            &ctx.vault,
             &ctx.bob,
-            String::from("PSP22::approve"),
-            Some(vec![ctx.ed.to_string(), 1000.to_string()]),
+            String::from("IVault::adjust_compound_bounty"),
+            Some(vec![String::from("100"), (100e12 as u128).to_string(), helpers::DAY.to_string()]),
             None,
-            helpers::transcoder_share_token(),
-        ).unwrap();
-
-        // Ed transfers 1k of Bob's sA0
-        helpers::call_function(
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let sess = helpers::update_days(sess, 6);
+        match helpers::call_function(
             sess,
-            &ctx.share_token,
-            &ctx.ed, // previously approved
-            String::from("PSP22::transfer_from"),
-            Some(vec![ctx.bob.to_string(),ctx.ed.to_string(),1000.to_string(),"[]".to_string()]),
+            &ctx.vault,
+            &ctx.bob,
+            String::from("IVault::execute_compound_bounty"),
             None,
-            helpers::transcoder_share_token(),
-        ).unwrap();
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because fee delay has not passed"),
+            Err(_) => (),
+        };
     }
     #[test]
-    fn test_compound_call() -> Result<(), Box<dyn Error>> {
+    fn test_payout_and_compound() -> Result<(), Box<dyn Error>> {
         let ctx = setup().unwrap();
 
         // Stake 1 AZERO
         let deposit_amount = 1e12 as u128;
-        let (shares, mut sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
-        assert_eq!(shares, deposit_amount); // 1:1 ratio
+        let (_, mut sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
 
-        // Fund nominator agents to simulate AZERO being claimed
+        // Fund nominator agents to simulate AZERO being paid out
         let mock_reward = 10_000;
         sess.chain_api().add_tokens(ctx.nominators[0].clone(), mock_reward);
         sess.chain_api().add_tokens(ctx.nominators[1].clone(), mock_reward);
 
-        // Compound
+        // Anyone can claim and compound
         let sess = helpers::call_function(
             sess,
             &ctx.vault,
-            &ctx.bob,
-            String::from("IVault::compound"),
-            None,
+            &ctx.alice,
+            String::from("IVault::payout_and_compound"),
+            Some(vec![String::from("[1, 2]"), String::from("0"), String::from("2")]),
             None,
             helpers::transcoder_vault(),
         )
@@ -1095,34 +3302,140 @@ mod tests {
 
         Ok(())
     }
-
     #[test]
-    fn test_compound_before_initial_stake() -> Result<(), Box<dyn Error>> {
+    fn test_payout_and_compound_agent_range() -> Result<(), Box<dyn Error>> {
         let ctx = setup().unwrap();
-        let mut sess = ctx.sess;
 
-        // Send funds to an agent
-        sess.chain_api().add_tokens(ctx.nominators[0].clone(), 1000);
+        // Stake 1 AZERO
+        let deposit_amount = 1e12 as u128;
+        let (_, mut sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
 
-        // Compound
+        // Fund nominator agents to simulate AZERO being paid out
+        let mock_reward = 10_000;
+        sess.chain_api().add_tokens(ctx.nominators[0].clone(), mock_reward);
+        sess.chain_api().add_tokens(ctx.nominators[1].clone(), mock_reward);
+
+        // Only the first agent is claimed and compounded
         let sess = helpers::call_function(
             sess,
             &ctx.vault,
-            &ctx.bob,
-            String::from("IVault::compound"),
-            None,
+            &ctx.alice,
+            String::from("IVault::payout_and_compound"),
+            Some(vec![String::from("[1, 2]"), String::from("0"), String::from("1")]),
             None,
             helpers::transcoder_vault(),
         )
             .unwrap();
 
         let (total_pooled, sess) = helpers::get_total_pooled(sess, &ctx.vault).unwrap();
-        assert_eq!(total_pooled, 1000);
+        assert_eq!(total_pooled, deposit_amount + mock_reward);
 
-        let deposit_amount = 1e12 as u128;
-        let (shares, _sess) = helpers::call_stake(sess, &ctx.vault, &ctx.share_token, &ctx.bob, deposit_amount).unwrap();
-        assert_eq!(shares, deposit_amount); // 1:1 ratio
+        // Next call resumes from the compound cursor
+        let sess = helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.alice,
+            String::from("IVault::get_compound_cursor"),
+            None,
+            None,
+            helpers::transcoder_vault(),
+        )
+            .unwrap();
+        let cursor: Result<u64, drink::errors::LangError> = sess.last_call_return().unwrap();
+        assert_eq!(cursor.unwrap(), 1);
 
         Ok(())
     }
+    #[test]
+    fn test_payout_and_compound_panic_because_too_many_eras() {
+        let ctx = setup().unwrap();
+        let (_, sess) = helpers::call_stake(ctx.sess, &ctx.vault, &ctx.share_token, &ctx.bob, 1e12 as u128).unwrap();
+
+        match helpers::call_function(
+            sess,
+            &ctx.vault,
+            &ctx.alice,
+            String::from("IVault::payout_and_compound"),
+            Some(vec![String::from("[1, 2, 3, 4, 5, 6, 7, 8, 9]"), String::from("0"), String::from("2")]),
+            None,
+            helpers::transcoder_vault(),
+        ) {
+            Ok(_) => panic!("Should panic because more eras than MAX_PAYOUT_ERAS are claimed"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_update_validators() {
+        let ctx = setup().unwrap();
+
+        let (validators, sess) = helpers::get_validators(ctx.sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        assert_eq!(validators, vec![ctx.validators[0].clone()]);
+
+        let sess = helpers::call_update_validators(
+            sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+            vec![ctx.validators[0].to_string(), ctx.validators[2].to_string()],
+        )
+            .unwrap();
+
+        let (validators, _sess) = helpers::get_validators(sess, &ctx.registry, &ctx.nominators[0]).unwrap();
+        assert_eq!(validators, vec![ctx.validators[0].clone(), ctx.validators[2].clone()]);
+    }
+    #[test]
+    fn test_update_validators_panic_because_caller_restricted() {
+        let ctx = setup().unwrap();
+
+        match helpers::call_update_validators(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.alice, // not bob
+            &ctx.nominators[0],
+            vec![ctx.validators[2].to_string()],
+        ) {
+            Ok(_) => panic!("Should panic because caller does not have the UpdateAgents role (Bob)"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_update_validators_panic_because_duplicate_validator() {
+        let ctx = setup().unwrap();
+
+        match helpers::call_update_validators(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.nominators[0],
+            vec![ctx.validators[2].to_string(), ctx.validators[2].to_string()],
+        ) {
+            Ok(_) => panic!("Should panic because a validator is nominated twice"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_update_validators_panic_because_above_max_nominations() {
+        let ctx = setup().unwrap();
+
+        let validators = (0..17u8).map(|i| AccountId32::new([i + 100; 32]).to_string()).collect();
+        match helpers::call_update_validators(ctx.sess, &ctx.registry, &ctx.bob, &ctx.nominators[0], validators) {
+            Ok(_) => panic!("Should panic because more validators than MAX_NOMINATIONS are nominated"),
+            Err(_) => (),
+        };
+    }
+    #[test]
+    fn test_update_validators_panic_because_agent_not_found() {
+        let ctx = setup().unwrap();
+
+        match helpers::call_update_validators(
+            ctx.sess,
+            &ctx.registry,
+            &ctx.bob,
+            &ctx.validators[0],
+            vec![ctx.validators[2].to_string()],
+        ) {
+            Ok(_) => panic!("Should panic because the account is not an agent"),
+            Err(_) => (),
+        };
+    }
 }
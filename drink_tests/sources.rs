@@ -22,11 +22,21 @@ pub fn hash_registry() -> String {
     let artifact: Artifact = from_str(&json).unwrap();
     artifact.source.hash
 }
+pub fn hash_vault() -> String {
+    let json = read_to_string("../deployments/development/vault/vault.json").unwrap();
+    let artifact: Artifact = from_str(&json).expect("Should extract hash from vault.json");
+    artifact.source.hash
+}
 pub fn hash_share_token() -> String {
     let json = read_to_string("../deployments/development/share_token/share_token.json").unwrap();
     let artifact: Artifact = from_str(&json).expect("Should extract hash from share_token.json");
     artifact.source.hash
 }
+pub fn hash_unlock_ticket() -> String {
+    let json = read_to_string("../deployments/development/unlock_ticket/unlock_ticket.json").unwrap();
+    let artifact: Artifact = from_str(&json).expect("Should extract hash from unlock_ticket.json");
+    artifact.source.hash
+}
 
 pub fn hash_nominator() -> String {
     let json = read_to_string("../deployments/development/mock_nominator/mock_nominator.json").unwrap();
@@ -52,6 +62,14 @@ pub fn transcoder_share_token() -> Option<Rc<ContractMessageTranscoder>> {
             .expect("Failed to create transcoder"),
     ))
 }
+pub fn transcoder_unlock_ticket() -> Option<Rc<ContractMessageTranscoder>> {
+    Some(Rc::new(
+        ContractMessageTranscoder::load(PathBuf::from(
+            "../deployments/development/unlock_ticket/unlock_ticket.json",
+        ))
+            .expect("Failed to create transcoder"),
+    ))
+}
 pub fn transcoder_vault() -> Option<Rc<ContractMessageTranscoder>> {
     Some(Rc::new(
         ContractMessageTranscoder::load(PathBuf::from(
@@ -60,6 +78,14 @@ pub fn transcoder_vault() -> Option<Rc<ContractMessageTranscoder>> {
             .expect("Failed to create transcoder"),
     ))
 }
+pub fn transcoder_multisig() -> Option<Rc<ContractMessageTranscoder>> {
+    Some(Rc::new(
+        ContractMessageTranscoder::load(PathBuf::from(
+            "../deployments/development/multisig/multisig.json",
+        ))
+            .expect("Failed to create transcoder"),
+    ))
+}
 pub fn transcoder_nominator() -> Option<Rc<ContractMessageTranscoder>> {
     Some(Rc::new(
         ContractMessageTranscoder::load(PathBuf::from(
@@ -79,10 +105,18 @@ pub fn bytes_share_token() -> Vec<u8> {
     read("../deployments/development/share_token/share_token.wasm")
         .expect("Failed to find or read contract file")
 }
+pub fn bytes_unlock_ticket() -> Vec<u8> {
+    read("../deployments/development/unlock_ticket/unlock_ticket.wasm")
+        .expect("Failed to find or read contract file")
+}
 pub fn bytes_vault() -> Vec<u8> {
     read("../deployments/development/vault/vault.wasm")
         .expect("Failed to find or read contract file")
 }
+pub fn bytes_multisig() -> Vec<u8> {
+    read("../deployments/development/multisig/multisig.wasm")
+        .expect("Failed to find or read contract file")
+}
 pub fn bytes_nominator() -> Vec<u8> {
     read("../deployments/development/mock_nominator/mock_nominator.wasm")
         .expect("Failed to find or read contract file")
@@ -59,6 +59,16 @@ const main = async (validators: string[]) => {
     'deploy_hash',
   )
 
+  console.log(`Deploying code hash: 'unlock_ticket' ...`)
+  const ticket_data = await getDeploymentData('unlock_ticket')
+  const unlock_ticket = await deployContract(
+    api,
+    account,
+    ticket_data.abi,
+    ticket_data.wasm,
+    'deploy_hash',
+  )
+
   console.log(`Deploying code hash: 'nomination_agent' ...`)
   const nomination_agent_data = await getDeploymentData('nomination_agent')
   console.log(`Data hash: ${nomination_agent_data.abi.source.hash}`)
@@ -81,7 +91,7 @@ const main = async (validators: string[]) => {
     vault_data.abi,
     vault_data.wasm,
     'new',
-    [token_data.abi.source.hash, registry_data.abi.source.hash, nomination_agent_data.abi.source.hash, eraDurationMs],
+    [token_data.abi.source.hash, ticket_data.abi.source.hash, registry_data.abi.source.hash, nomination_agent_data.abi.source.hash, eraDurationMs],
   )
 
   const vault_instance = new ContractPromise(api, vault_data.abi, vault.address)
@@ -109,6 +119,16 @@ const main = async (validators: string[]) => {
   share_token.address = decodeOutput(share_token_contract_result, vault_instance, 'iVault::get_share_token_contract').output
   console.log(`Share Token Address: ${share_token.address}`)
 
+  console.log('Fetching unlock ticket contract ...')
+  const unlock_ticket_contract_result = await contractQuery(
+    api,
+    '',
+    vault_instance,
+    'iVault::get_unlock_ticket_contract',
+  )
+  unlock_ticket.address = decodeOutput(unlock_ticket_contract_result, vault_instance, 'iVault::get_unlock_ticket_contract').output
+  console.log(`Unlock Ticket Address: ${unlock_ticket.address}`)
+
   console.log('===== Agent Configuration =====')
 
   for (const validator of validators) {
@@ -150,12 +170,14 @@ const main = async (validators: string[]) => {
     vault: vault.address,
     registry: registry.address,
     share_token: share_token.address,
+    unlock_ticket: unlock_ticket.address,
     ...agents.reduce((obj, a, i) => ({...obj, [`agent[${i}]`]: a.address}), {}),
   })
 
   await writeContractAddresses(chain.network, {
     vault,
     share_token,
+    unlock_ticket,
     registry,
   })
 }
@@ -0,0 +1,16 @@
+#[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum AccessControlError {
+    /// Caller is not a member of the admin role
+    MissingRole,
+    /// Account is already a member of the role
+    RoleAlreadyGranted,
+    /// Account is not a member of the role
+    RoleNotGranted,
+    /// Account has not been proposed for the role
+    NoPendingGrant,
+    /// Role has reached `MAX_ROLE_MEMBERS`
+    MaxRoleMembers,
+    /// Removing the account would leave `DEFAULT_ADMIN_ROLE` without members
+    LastAdmin,
+}
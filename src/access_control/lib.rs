@@ -0,0 +1,171 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod errors;
+
+use crate::errors::AccessControlError;
+use ink::{
+    prelude::vec::Vec,
+    primitives::AccountId,
+    storage::Mapping,
+};
+
+pub type RoleId = u32;
+
+/// Role which administers every role unless configured otherwise
+pub const DEFAULT_ADMIN_ROLE: RoleId = 0;
+/// Maximum number of members a single role can have
+pub const MAX_ROLE_MEMBERS: usize = 10;
+
+/// Role based access control shared by the protocol contracts
+///
+/// Roles can have several members and each role is administered by another role.
+/// Granting a role is two-step: a member of the admin role proposes an account
+/// which must then accept, preventing roles from being handed to mistyped accounts.
+///
+/// Functions take the `caller` explicitly and never emit events,
+/// the embedding contract is responsible for both.
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct AccessControl {
+    /// members of each role
+    members: Mapping<RoleId, Vec<AccountId>>,
+    /// admin role of each role, `DEFAULT_ADMIN_ROLE` when unset
+    admin_roles: Mapping<RoleId, RoleId>,
+    /// accounts proposed for each role, pending acceptance, with the admin role at proposal time
+    pending: Mapping<(RoleId, AccountId), RoleId>,
+}
+
+impl AccessControl {
+    pub fn has_role(&self, role: RoleId, account: AccountId) -> bool {
+        self.members.get(role).unwrap_or_default().contains(&account)
+    }
+
+    pub fn ensure_role(&self, role: RoleId, account: AccountId) -> Result<(), AccessControlError> {
+        if !self.has_role(role, account) {
+            return Err(AccessControlError::MissingRole);
+        }
+        Ok(())
+    }
+
+    pub fn get_role_admin(&self, role: RoleId) -> RoleId {
+        self.admin_roles.get(role).unwrap_or(DEFAULT_ADMIN_ROLE)
+    }
+
+    pub fn get_role_members(&self, role: RoleId) -> Vec<AccountId> {
+        self.members.get(role).unwrap_or_default()
+    }
+
+    pub fn is_pending(&self, role: RoleId, account: AccountId) -> bool {
+        self.pending.contains((role, account))
+    }
+
+    /// Sets the role which administers `role`
+    /// Performs no permission checks, intended for constructors
+    pub fn set_role_admin(&mut self, role: RoleId, admin_role: RoleId) {
+        self.admin_roles.insert(role, &admin_role);
+    }
+
+    /// Adds `account` to `role` without requiring acceptance
+    /// Performs no permission checks, intended for constructors
+    pub fn grant(&mut self, role: RoleId, account: AccountId) -> Result<(), AccessControlError> {
+        let mut members = self.members.get(role).unwrap_or_default();
+        if members.contains(&account) {
+            return Err(AccessControlError::RoleAlreadyGranted);
+        }
+        if members.len() >= MAX_ROLE_MEMBERS {
+            return Err(AccessControlError::MaxRoleMembers);
+        }
+        members.push(account);
+        self.members.insert(role, &members);
+        Ok(())
+    }
+
+    /// Removes `account` from `role`
+    /// Performs no permission checks
+    pub fn revoke(&mut self, role: RoleId, account: AccountId) -> Result<(), AccessControlError> {
+        let mut members = self.members.get(role).unwrap_or_default();
+        let index = members
+            .iter()
+            .position(|m| *m == account)
+            .ok_or(AccessControlError::RoleNotGranted)?;
+        members.swap_remove(index);
+        self.members.insert(role, &members);
+        Ok(())
+    }
+
+    /// Proposes granting `role` to `account`
+    ///
+    /// Caller must be a member of the admin role
+    /// `account` must call `accept_role` to become a member
+    pub fn propose_role(
+        &mut self,
+        caller: AccountId,
+        role: RoleId,
+        account: AccountId,
+    ) -> Result<(), AccessControlError> {
+        let admin_role = self.get_role_admin(role);
+        self.ensure_role(admin_role, caller)?;
+        if self.has_role(role, account) {
+            return Err(AccessControlError::RoleAlreadyGranted);
+        }
+        self.pending.insert((role, account), &admin_role);
+        Ok(())
+    }
+
+    /// Accepts a pending grant of `role` to the caller
+    ///
+    /// Grants proposed before the admin role of `role` changed are void
+    pub fn accept_role(&mut self, caller: AccountId, role: RoleId) -> Result<(), AccessControlError> {
+        match self.pending.take((role, caller)) {
+            Some(admin_role) if admin_role == self.get_role_admin(role) => self.grant(role, caller),
+            _ => Err(AccessControlError::NoPendingGrant),
+        }
+    }
+
+    /// Cancels a pending grant of `role` to `account`
+    ///
+    /// Caller must be a member of the admin role
+    pub fn cancel_role(
+        &mut self,
+        caller: AccountId,
+        role: RoleId,
+        account: AccountId,
+    ) -> Result<(), AccessControlError> {
+        self.ensure_role(self.get_role_admin(role), caller)?;
+        if self.pending.take((role, account)).is_none() {
+            return Err(AccessControlError::NoPendingGrant);
+        }
+        Ok(())
+    }
+
+    /// Removes `account` from `role`
+    ///
+    /// Caller must be a member of the admin role
+    /// The last member of `DEFAULT_ADMIN_ROLE` cannot be revoked
+    pub fn revoke_role(
+        &mut self,
+        caller: AccountId,
+        role: RoleId,
+        account: AccountId,
+    ) -> Result<(), AccessControlError> {
+        self.ensure_role(self.get_role_admin(role), caller)?;
+        self.ensure_not_last_admin(role, account)?;
+        self.revoke(role, account)
+    }
+
+    /// Removes the caller from `role`
+    ///
+    /// The last member of `DEFAULT_ADMIN_ROLE` cannot renounce it
+    pub fn renounce_role(&mut self, caller: AccountId, role: RoleId) -> Result<(), AccessControlError> {
+        self.ensure_not_last_admin(role, caller)?;
+        self.revoke(role, caller)
+    }
+
+    /// Prevents `DEFAULT_ADMIN_ROLE` from being left without members
+    fn ensure_not_last_admin(&self, role: RoleId, account: AccountId) -> Result<(), AccessControlError> {
+        if role == DEFAULT_ADMIN_ROLE && self.get_role_members(role) == [account] {
+            return Err(AccessControlError::LastAdmin);
+        }
+        Ok(())
+    }
+}
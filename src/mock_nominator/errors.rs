@@ -4,4 +4,6 @@ pub enum RuntimeError {
     CallRuntimeFailed,
     Unauthorized,
     Active,
+    InvalidSlash,
+    InvalidValidators,
 }
@@ -3,18 +3,23 @@
 pub mod errors;
 pub mod traits;
 
+/// mirrors `nomination_agent::MAX_NOMINATIONS`
+pub const MAX_NOMINATIONS: usize = 16;
+
 #[ink::contract]
 mod mock_nominator {
     use crate::errors::RuntimeError;
     use crate::traits::INominationAgent;
+    use crate::MAX_NOMINATIONS;
     use ink::env::Error as EnvError;
+    use ink::prelude::vec::Vec;
 
     #[ink(storage)]
     pub struct NominationAgent {
         vault: AccountId,
         registry: AccountId,
         admin: AccountId,
-        validator: AccountId,
+        validators: Vec<AccountId>,
         staked: u128,
         unbonding: u128,
         creation_bond: u128,
@@ -37,7 +42,7 @@ mod mock_nominator {
                 vault: account_id,
                 registry: account_id,
                 admin: account_id,
-                validator: account_id,
+                validators: Vec::new(),
                 staked: 0,
                 unbonding: 0,
                 creation_bond: 0,
@@ -62,7 +67,7 @@ mod mock_nominator {
                 vault,
                 registry: Self::env().caller(),
                 admin,
-                validator,
+                validators: [validator].to_vec(),
                 staked: 0,
                 unbonding: 0,
                 creation_bond,
@@ -124,6 +129,62 @@ mod mock_nominator {
             Ok(compound_amount)
         }
 
+        #[ink(message, selector = 5)]
+        fn rebond(&mut self, amount: u128) -> Result<(), RuntimeError> {
+            if Self::env().caller() != self.vault {
+                return Err(RuntimeError::Unauthorized);
+            }
+            self.unbonding -= amount;
+            self.staked += amount;
+            Ok(())
+        }
+
+        #[ink(message, selector = 6)]
+        fn slash(&mut self, staked: u128, unbonding: u128) -> Result<(Balance, Balance), RuntimeError> {
+            if Self::env().caller() != self.vault {
+                return Err(RuntimeError::Unauthorized);
+            }
+            if staked > self.staked || unbonding > self.unbonding {
+                return Err(RuntimeError::InvalidSlash);
+            }
+            if staked == self.staked && unbonding == self.unbonding {
+                return Err(RuntimeError::InvalidSlash);
+            }
+            let staked_slashed = self.staked - staked;
+            let unbonding_slashed = self.unbonding - unbonding;
+            self.staked = staked;
+            self.unbonding = unbonding;
+            // Mock burning the slashed AZERO
+            Self::env().transfer(AccountId::from([0u8; 32]), staked_slashed + unbonding_slashed)?;
+            Ok((staked_slashed, unbonding_slashed))
+        }
+
+        #[ink(message, selector = 7)]
+        fn payout(&mut self, _era: u32) -> Result<(), RuntimeError> {
+            if Self::env().caller() != self.vault {
+                return Err(RuntimeError::Unauthorized);
+            }
+            // Mock rewards are funded directly to the agent
+            Ok(())
+        }
+
+        #[ink(message, selector = 8)]
+        fn set_validators(&mut self, validators: Vec<AccountId>) -> Result<(), RuntimeError> {
+            if Self::env().caller() != self.registry {
+                return Err(RuntimeError::Unauthorized);
+            }
+            if validators.is_empty() || validators.len() > MAX_NOMINATIONS {
+                return Err(RuntimeError::InvalidValidators);
+            }
+            for (i, v) in validators.iter().enumerate() {
+                if validators[..i].contains(v) {
+                    return Err(RuntimeError::InvalidValidators);
+                }
+            }
+            self.validators = validators;
+            Ok(())
+        }
+
         #[ink(message, selector = 12)]
         fn get_staked_value(&self) -> Balance {
             self.staked
@@ -145,8 +206,8 @@ mod mock_nominator {
         }
 
         #[ink(message)]
-        fn get_validator(&self) -> AccountId {
-            self.validator
+        fn get_validators(&self) -> Vec<AccountId> {
+            self.validators.clone()
         }
 
         #[ink(message, selector = 101)]
@@ -0,0 +1,19 @@
+use ink::prelude::string::String;
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum MultisigError {
+    InvalidOwners,
+    InvalidThreshold,
+    NotOwner,
+    TransactionNotFound,
+    AlreadyConfirmed,
+    NotConfirmed,
+    AlreadyExecuted,
+    ThresholdNotReached,
+    TransactionFailed,
+    /// An interaction with ink! environment has failed
+    // NOTE: We're representing the `ink::env::Error` as `String` b/c the
+    // type does not have Encode/Decode implemented.
+    InkEnvError(String),
+}
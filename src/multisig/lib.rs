@@ -0,0 +1,304 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub mod errors;
+pub mod traits;
+
+#[ink::contract]
+pub mod multisig {
+
+    use crate::errors::MultisigError;
+    use crate::traits::IMultisig;
+    use ink::{
+        env::{
+            call::{build_call, ExecutionInput, Selector},
+            Error as InkEnvError,
+        },
+        prelude::{format, vec::Vec},
+        storage::Mapping,
+    };
+
+    // Maximum number of owners, bounds iteration over `owners`
+    pub const MAX_OWNERS: usize = 20;
+
+    impl From<InkEnvError> for MultisigError {
+        fn from(e: InkEnvError) -> Self {
+            MultisigError::InkEnvError(format!("{:?}", e))
+        }
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Transaction {
+        // Contract being called
+        pub callee: AccountId,
+        // Selector of the message being called
+        pub selector: [u8; 4],
+        // SCALE encoded arguments of the message
+        pub input: Vec<u8>,
+        // AZERO forwarded with the call
+        pub transferred_value: Balance,
+        // Number of owners which confirmed the transaction
+        pub confirmations: u32,
+        pub executed: bool,
+    }
+
+    /// Appends already encoded arguments to the call without prefixing their length
+    struct CallInput<'a>(&'a [u8]);
+
+    impl<'a> scale::Encode for CallInput<'a> {
+        fn encode_to<T: scale::Output + ?Sized>(&self, dest: &mut T) {
+            dest.write(self.0);
+        }
+    }
+
+    /// Whether the called message returned `Ok`, the returned value itself is discarded
+    /// Output of reverted calls is still decoded, so failures are detected from the `Result` variant
+    struct CallOutcome(bool);
+
+    impl scale::Decode for CallOutcome {
+        fn decode<I: scale::Input>(input: &mut I) -> Result<Self, scale::Error> {
+            let is_ok = input.read_byte()? == 0;
+            while input.read_byte().is_ok() {}
+            Ok(CallOutcome(is_ok))
+        }
+    }
+
+    #[ink(event)]
+    pub struct TransactionProposed {
+        #[ink(topic)]
+        id: u32,
+        proposer: AccountId,
+        callee: AccountId,
+        selector: [u8; 4],
+    }
+    #[ink(event)]
+    pub struct TransactionConfirmed {
+        #[ink(topic)]
+        id: u32,
+        owner: AccountId,
+    }
+    #[ink(event)]
+    pub struct ConfirmationRevoked {
+        #[ink(topic)]
+        id: u32,
+        owner: AccountId,
+    }
+    #[ink(event)]
+    pub struct TransactionExecuted {
+        #[ink(topic)]
+        id: u32,
+        executor: AccountId,
+    }
+
+    #[ink(storage)]
+    pub struct Multisig {
+        // Accounts which can propose, confirm, and execute transactions
+        pub owners: Vec<AccountId>,
+        // Number of confirmations required to execute a transaction
+        pub threshold: u32,
+        // Proposed transactions indexed by id
+        pub transactions: Mapping<u32, Transaction>,
+        // Confirmations indexed by transaction id and owner
+        pub confirmations: Mapping<(u32, AccountId), ()>,
+        // Id assigned to the next proposed transaction
+        pub next_transaction_id: u32,
+    }
+
+    impl Multisig {
+        /// Owners and threshold are fixed, rotate them by deploying a new multisig
+        /// and transferring roles to it through this one.
+        #[ink(constructor)]
+        pub fn new(owners: Vec<AccountId>, threshold: u32) -> Result<Self, MultisigError> {
+            if owners.is_empty() || owners.len() > MAX_OWNERS {
+                return Err(MultisigError::InvalidOwners);
+            }
+            for (i, owner) in owners.iter().enumerate() {
+                if owners[i + 1..].contains(owner) {
+                    return Err(MultisigError::InvalidOwners);
+                }
+            }
+            if threshold == 0 || threshold as usize > owners.len() {
+                return Err(MultisigError::InvalidThreshold);
+            }
+
+            Ok(Self {
+                owners,
+                threshold,
+                transactions: Mapping::default(),
+                confirmations: Mapping::default(),
+                next_transaction_id: 0,
+            })
+        }
+
+        fn ensure_owner(&self) -> Result<AccountId, MultisigError> {
+            let caller = Self::env().caller();
+            if !self.owners.contains(&caller) {
+                return Err(MultisigError::NotOwner);
+            }
+            Ok(caller)
+        }
+
+        fn get_pending_transaction(&self, id: u32) -> Result<Transaction, MultisigError> {
+            let transaction = self
+                .transactions
+                .get(id)
+                .ok_or(MultisigError::TransactionNotFound)?;
+            if transaction.executed {
+                return Err(MultisigError::AlreadyExecuted);
+            }
+            Ok(transaction)
+        }
+    }
+
+    impl IMultisig for Multisig {
+        /// Proposes a call to another contract
+        ///
+        /// Caller must be an owner.
+        /// Counts as the caller's confirmation.
+        #[ink(message)]
+        fn propose(
+            &mut self,
+            callee: AccountId,
+            selector: [u8; 4],
+            input: Vec<u8>,
+            transferred_value: Balance,
+        ) -> Result<u32, MultisigError> {
+            let caller = self.ensure_owner()?;
+
+            let id = self.next_transaction_id;
+            self.next_transaction_id = id + 1;
+
+            self.transactions.insert(
+                id,
+                &Transaction {
+                    callee,
+                    selector,
+                    input,
+                    transferred_value,
+                    confirmations: 1,
+                    executed: false,
+                },
+            );
+            self.confirmations.insert((id, caller), &());
+
+            Self::env().emit_event(TransactionProposed {
+                id,
+                proposer: caller,
+                callee,
+                selector,
+            });
+            Self::env().emit_event(TransactionConfirmed { id, owner: caller });
+
+            Ok(id)
+        }
+
+        /// Confirms a pending transaction
+        ///
+        /// Caller must be an owner.
+        #[ink(message)]
+        fn confirm(&mut self, id: u32) -> Result<(), MultisigError> {
+            let caller = self.ensure_owner()?;
+            let mut transaction = self.get_pending_transaction(id)?;
+
+            if self.confirmations.contains((id, caller)) {
+                return Err(MultisigError::AlreadyConfirmed);
+            }
+
+            transaction.confirmations += 1;
+            self.transactions.insert(id, &transaction);
+            self.confirmations.insert((id, caller), &());
+
+            Self::env().emit_event(TransactionConfirmed { id, owner: caller });
+
+            Ok(())
+        }
+
+        /// Revokes the caller's confirmation of a pending transaction
+        ///
+        /// Caller must be an owner.
+        #[ink(message)]
+        fn revoke_confirmation(&mut self, id: u32) -> Result<(), MultisigError> {
+            let caller = self.ensure_owner()?;
+            let mut transaction = self.get_pending_transaction(id)?;
+
+            if self.confirmations.take((id, caller)).is_none() {
+                return Err(MultisigError::NotConfirmed);
+            }
+
+            transaction.confirmations -= 1;
+            self.transactions.insert(id, &transaction);
+
+            Self::env().emit_event(ConfirmationRevoked { id, owner: caller });
+
+            Ok(())
+        }
+
+        /// Executes a transaction which reached the threshold
+        ///
+        /// Caller must be an owner.
+        /// `transferred_value` is paid from the multisig balance, which can be topped up by this call.
+        /// Called message must return a `Result`, reverts if it returns `Err`.
+        #[ink(message, payable)]
+        fn execute(&mut self, id: u32) -> Result<(), MultisigError> {
+            let caller = self.ensure_owner()?;
+            let mut transaction = self.get_pending_transaction(id)?;
+
+            if transaction.confirmations < self.threshold {
+                return Err(MultisigError::ThresholdNotReached);
+            }
+
+            // Mark as executed before calling out to prevent replays
+            transaction.executed = true;
+            self.transactions.insert(id, &transaction);
+
+            let outcome = build_call::<Environment>()
+                .call(transaction.callee)
+                .transferred_value(transaction.transferred_value)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(transaction.selector))
+                        .push_arg(CallInput(&transaction.input)),
+                )
+                .returns::<CallOutcome>()
+                .try_invoke()?
+                .map_err(|e| MultisigError::InkEnvError(format!("{:?}", e)))?;
+            if !outcome.0 {
+                return Err(MultisigError::TransactionFailed);
+            }
+
+            Self::env().emit_event(TransactionExecuted {
+                id,
+                executor: caller,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_owners(&self) -> Vec<AccountId> {
+            self.owners.clone()
+        }
+
+        #[ink(message)]
+        fn get_threshold(&self) -> u32 {
+            self.threshold
+        }
+
+        #[ink(message)]
+        fn get_transaction(&self, id: u32) -> Option<Transaction> {
+            self.transactions.get(id)
+        }
+
+        #[ink(message)]
+        fn get_next_transaction_id(&self) -> u32 {
+            self.next_transaction_id
+        }
+
+        #[ink(message)]
+        fn is_confirmed_by(&self, id: u32, owner: AccountId) -> bool {
+            self.confirmations.contains((id, owner))
+        }
+    }
+}
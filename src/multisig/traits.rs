@@ -0,0 +1,45 @@
+use crate::errors::MultisigError;
+use crate::multisig::Transaction;
+use ink::{
+    env::{DefaultEnvironment, Environment},
+    primitives::AccountId,
+    prelude::vec::Vec,
+};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+
+#[ink::trait_definition]
+pub trait IMultisig {
+    #[ink(message)]
+    fn propose(
+        &mut self,
+        callee: AccountId,
+        selector: [u8; 4],
+        input: Vec<u8>,
+        transferred_value: Balance,
+    ) -> Result<u32, MultisigError>;
+
+    #[ink(message)]
+    fn confirm(&mut self, id: u32) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn revoke_confirmation(&mut self, id: u32) -> Result<(), MultisigError>;
+
+    #[ink(message, payable)]
+    fn execute(&mut self, id: u32) -> Result<(), MultisigError>;
+
+    #[ink(message)]
+    fn get_owners(&self) -> Vec<AccountId>;
+
+    #[ink(message)]
+    fn get_threshold(&self) -> u32;
+
+    #[ink(message)]
+    fn get_transaction(&self, id: u32) -> Option<Transaction>;
+
+    #[ink(message)]
+    fn get_next_transaction_id(&self) -> u32;
+
+    #[ink(message)]
+    fn is_confirmed_by(&self, id: u32, owner: AccountId) -> bool;
+}
@@ -59,6 +59,16 @@ pub enum StakingCall {
     },
     #[codec(index = 6)]
     Chill,
+    #[codec(index = 18)]
+    PayoutStakers {
+        validator_stash: AccountId,
+        era: u32,
+    },
+    #[codec(index = 19)]
+    Rebond {
+        #[codec(compact)]
+        value: u128,
+    },
 }
 
 #[derive(scale::Encode)]
@@ -4,19 +4,28 @@ mod data;
 pub mod errors;
 pub mod traits;
 
+/// maximum validators nominated by a single agent
+///
+/// Pinned to `pallet_staking::Config::MaxNominations` of the Aleph Zero runtime the agents are deployed on.
+/// Nominating more targets than the runtime allows fails with `RuntimeError::CallRuntimeFailed`,
+/// so this must be lowered through an agent code upgrade (`set_agent_code`) before the runtime value is.
+pub const MAX_NOMINATIONS: usize = 16;
+
 #[ink::contract]
 pub mod nomination_agent {
     use crate::data::{MultiAddress, RewardDestination, RuntimeCall, StakingCall};
     use crate::errors::RuntimeError;
     use crate::traits::INominationAgent;
+    use crate::MAX_NOMINATIONS;
     use ink::env::Error as EnvError;
+    use ink::prelude::vec::Vec;
 
     #[ink(storage)]
     pub struct NominationAgent {
         vault: AccountId,
         registry: AccountId,
         admin: AccountId,
-        validator: AccountId,
+        validators: Vec<AccountId>,
         staked: u128,
         unbonding: u128,
         creation_bond: u128,
@@ -39,7 +48,7 @@ pub mod nomination_agent {
                 vault: account_id,
                 registry: account_id,
                 admin: account_id,
-                validator: account_id,
+                validators: Vec::new(),
                 staked: 0,
                 unbonding: 0,
                 creation_bond: 0,
@@ -58,7 +67,7 @@ pub mod nomination_agent {
                 vault,
                 registry: Self::env().caller(),
                 admin,
-                validator,
+                validators: [validator].to_vec(),
                 staked: 0,
                 unbonding: 0,
                 creation_bond,
@@ -72,15 +81,18 @@ pub mod nomination_agent {
                 }))
                 .unwrap();
 
-            nomination_agent
-                .env()
-                .call_runtime(&RuntimeCall::Staking(StakingCall::Nominate {
-                    targets: [MultiAddress::Id(validator)].to_vec(),
-                }))
-                .unwrap();
+            nomination_agent.nominate().unwrap();
 
             nomination_agent
         }
+
+        fn nominate(&self) -> Result<(), RuntimeError> {
+            self.env()
+                .call_runtime(&RuntimeCall::Staking(StakingCall::Nominate {
+                    targets: self.validators.iter().map(|v| MultiAddress::Id(*v)).collect(),
+                }))?;
+            Ok(())
+        }
     }
 
     impl INominationAgent for NominationAgent {
@@ -197,6 +209,110 @@ pub mod nomination_agent {
             Ok(compounded)
         }
 
+        /// Moves unbonding AZERO back into the active bond
+        ///
+        /// Staking rebonds the most recent unlocking chunks first,
+        /// so older chunks are never delayed
+        #[ink(message, selector = 5)]
+        fn rebond(&mut self, amount: u128) -> Result<(), RuntimeError> {
+            // Restricted to vault
+            if Self::env().caller() != self.vault {
+                return Err(RuntimeError::Unauthorized);
+            }
+
+            self.staked += amount;
+            self.unbonding -= amount;
+
+            // Rebond AZERO
+            self.env()
+                .call_runtime(&RuntimeCall::Staking(StakingCall::Rebond {
+                    value: amount,
+                }))?;
+
+            Ok(())
+        }
+
+        /// Lowers the tracked stake and unbonding amounts to the real amounts after a validator slash
+        ///
+        /// `staked` is the protocol stake remaining in the staking ledger, excluding the initial bond
+        /// `unbonding` is the protocol AZERO remaining in the ledger's unlocking chunks
+        /// Returns the AZERO lost to the slash from the stake and from unbonding
+        #[ink(message, selector = 6)]
+        fn slash(&mut self, staked: u128, unbonding: u128) -> Result<(Balance, Balance), RuntimeError> {
+            // Restricted to vault
+            if Self::env().caller() != self.vault {
+                return Err(RuntimeError::Unauthorized);
+            }
+
+            if staked > self.staked || unbonding > self.unbonding {
+                return Err(RuntimeError::InvalidSlash);
+            }
+            if staked == self.staked && unbonding == self.unbonding {
+                return Err(RuntimeError::InvalidSlash);
+            }
+
+            let staked_slashed = self.staked - staked;
+            let unbonding_slashed = self.unbonding - unbonding;
+            self.staked = staked;
+            self.unbonding = unbonding;
+
+            Ok((staked_slashed, unbonding_slashed))
+        }
+
+        /// Claims the validators' staking rewards for an era on behalf of all of their nominators
+        ///
+        /// Rewards are paid to the agent's stash and bonded via `compound`
+        /// Validators which cannot be paid out, such as those already claimed, are skipped
+        /// Fails only when no validator could be paid out
+        #[ink(message, selector = 7)]
+        fn payout(&mut self, era: u32) -> Result<(), RuntimeError> {
+            // Restricted to vault
+            if Self::env().caller() != self.vault {
+                return Err(RuntimeError::Unauthorized);
+            }
+
+            let mut paid = false;
+            for validator in self.validators.iter() {
+                match self.env().call_runtime(&RuntimeCall::Staking(StakingCall::PayoutStakers {
+                    validator_stash: *validator,
+                    era,
+                })) {
+                    Ok(()) => paid = true,
+                    Err(e) => ink::env::debug_println!("Ignoring StakingCall::PayoutStakers error {:?}", e),
+                }
+            }
+
+            if !paid {
+                return Err(RuntimeError::CallRuntimeFailed);
+            }
+
+            Ok(())
+        }
+
+        /// Replaces the nominated validators
+        ///
+        /// Can only be called by registry
+        /// Must nominate between 1 and `MAX_NOMINATIONS` distinct validators
+        #[ink(message, selector = 8)]
+        fn set_validators(&mut self, validators: Vec<AccountId>) -> Result<(), RuntimeError> {
+            // Restricted to registry
+            if Self::env().caller() != self.registry {
+                return Err(RuntimeError::Unauthorized);
+            }
+
+            if validators.is_empty() || validators.len() > MAX_NOMINATIONS {
+                return Err(RuntimeError::InvalidValidators);
+            }
+            for (i, v) in validators.iter().enumerate() {
+                if validators[..i].contains(v) {
+                    return Err(RuntimeError::InvalidValidators);
+                }
+            }
+
+            self.validators = validators;
+            self.nominate()
+        }
+
         #[ink(message, selector = 12)]
         fn get_staked_value(&self) -> Balance {
             self.staked
@@ -218,13 +334,13 @@ pub mod nomination_agent {
         }
 
         #[ink(message)]
-        fn get_validator(&self) -> AccountId {
-            self.validator
+        fn get_validators(&self) -> Vec<AccountId> {
+            self.validators.clone()
         }
 
         /// Step 1 of 2 in finalizing the agent's lifecycle
         /// Performs the following actions:
-        ///     1) Removes the validator nomination
+        ///     1) Removes the validator nominations
         ///     2) Begins unbonding the initial bond
         ///
         /// Can only be called by registry
@@ -1,5 +1,5 @@
 use crate::errors::RuntimeError;
-use ink::primitives::AccountId;
+use ink::{primitives::AccountId, prelude::vec::Vec};
 
 #[ink::trait_definition]
 pub trait INominationAgent {
@@ -15,6 +15,18 @@ pub trait INominationAgent {
     #[ink(message, selector = 4)]
     fn compound(&mut self) -> Result<u128, RuntimeError>;
 
+    #[ink(message, selector = 5)]
+    fn rebond(&mut self, amount: u128) -> Result<(), RuntimeError>;
+
+    #[ink(message, selector = 6)]
+    fn slash(&mut self, staked: u128, unbonding: u128) -> Result<(u128, u128), RuntimeError>;
+
+    #[ink(message, selector = 7)]
+    fn payout(&mut self, era: u32) -> Result<(), RuntimeError>;
+
+    #[ink(message, selector = 8)]
+    fn set_validators(&mut self, validators: Vec<AccountId>) -> Result<(), RuntimeError>;
+
     #[ink(message, selector = 12)]
     fn get_staked_value(&self) -> u128;
 
@@ -28,7 +40,7 @@ pub trait INominationAgent {
     fn get_admin(&self) -> AccountId;
 
     #[ink(message)]
-    fn get_validator(&self) -> AccountId;
+    fn get_validators(&self) -> Vec<AccountId>;
 
     #[ink(message, selector = 101)]
     fn destroy(&mut self) -> Result<(), RuntimeError>;
@@ -1,3 +1,4 @@
+use access_control::errors::AccessControlError;
 use ink::prelude::string::String;
 
 #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -7,11 +8,17 @@ pub enum RegistryError {
     DuplicateAgent,
     AgentNotFound,
     ActiveAgent,
+    InvalidValidators,
     InvalidPermissions,
-    InvalidRole,
     NoChange,
+    Paused,
+    NoCodeAnnouncement,
+    CodeTimelock,
+    CodeHashMismatch,
+    NoCodeDelayProposal,
     /// An interaction with ink! environment has failed
     // NOTE: We're representing the `ink::env::Error` as `String` b/c the
     // type does not have Encode/Decode implemented.
     InkEnvError(String),
+    AccessControlError(AccessControlError),
 }
\ No newline at end of file
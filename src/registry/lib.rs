@@ -8,37 +8,44 @@ pub mod registry {
 
     use crate::errors::RegistryError;
     use crate::traits::IRegistry;
+    use access_control::{errors::AccessControlError, AccessControl, RoleId};
     use ink::{
         contract_ref,
-        env::Error as InkEnvError,
+        env::{
+            call::{build_call, ExecutionInput, Selector},
+            Error as InkEnvError,
+        },
         prelude::{format, vec::Vec},
         storage::Mapping,
         ToAccountId,
     };
     use nomination_agent::{nomination_agent::NominationAgentRef, traits::INominationAgent};
 
+    pub const DAY: u64 = 86400 * 1000;
+    // Initial delay between announcing and executing a code upgrade
+    // Adjustments are bounded by the Vault's minimum code delay which tracks its unlock cooldown
+    pub const MIN_CODE_DELAY: u64 = DAY * 15;
+
     impl From<InkEnvError> for RegistryError {
         fn from(e: InkEnvError) -> Self {
             RegistryError::InkEnvError(format!("{:?}", e))
         }
     }
 
-    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
-    #[cfg_attr(
-        feature = "std",
-        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
-    )]
-    pub struct Role {
-        admin: AccountId,
-        account: AccountId,
+    impl From<AccessControlError> for RegistryError {
+        fn from(e: AccessControlError) -> Self {
+            RegistryError::AccessControlError(e)
+        }
     }
 
-    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
     #[cfg_attr(
         feature = "std",
         derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
     )]
     pub enum RoleType {
+        // Administers every role, must remain the first variant (`DEFAULT_ADMIN_ROLE`)
+        Admin,
         // Permission to add new agents
         AddAgent,
         // Permission to update agent weights
@@ -47,6 +54,52 @@ pub mod registry {
         RemoveAgent,
         // Permission to set code hash aka "upgrade" logic
         SetCodeHash,
+        // Permission to pause operations, unpausing requires the Admin role
+        Pause,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum PauseType {
+        // Adding new agents
+        AddAgent,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum CodeTarget {
+        // Registry logic via `set_code`
+        Registry,
+        // Nomination agent logic via `set_agent_code`
+        Agent,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct CodeAnnouncement {
+        pub code_hash: [u8; 32],
+        // Earliest time the upgrade can be executed
+        pub execute_time: Timestamp,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct CodeDelayProposal {
+        pub new_delay: u64,
+        // Earliest time the decrease can be executed
+        pub execute_time: Timestamp,
     }
 
     #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
@@ -72,19 +125,68 @@ pub mod registry {
         new_weight: u64,
     }
     #[ink(event)]
+    pub struct ValidatorsUpdated {
+        #[ink(topic)]
+        agent: AccountId,
+        validators: Vec<AccountId>,
+    }
+    #[ink(event)]
     pub struct AgentDeleted {
         #[ink(topic)]
         agent: AccountId,
     }
     #[ink(event)]
-    pub struct RoleAccountChanged {
+    pub struct Paused {
+        pause_type: PauseType,
+    }
+    #[ink(event)]
+    pub struct Unpaused {
+        pause_type: PauseType,
+    }
+    #[ink(event)]
+    pub struct CodeAnnounced {
+        target: CodeTarget,
+        code_hash: [u8; 32],
+        execute_time: Timestamp,
+    }
+    #[ink(event)]
+    pub struct CodeAnnouncementCancelled {
+        target: CodeTarget,
+        code_hash: [u8; 32],
+    }
+    #[ink(event)]
+    pub struct CodeUpdated {
+        target: CodeTarget,
+        code_hash: [u8; 32],
+    }
+    #[ink(event)]
+    pub struct CodeDelayProposed {
+        new_delay: u64,
+        execute_time: Timestamp,
+    }
+    #[ink(event)]
+    pub struct CodeDelayAdjusted {
+        new_delay: u64,
+    }
+    #[ink(event)]
+    pub struct RoleGrantProposed {
         role_type: RoleType,
-        new_account: AccountId,
+        account: AccountId,
     }
     #[ink(event)]
-    pub struct RoleAdminChanged {
+    pub struct RoleGrantCancelled {
         role_type: RoleType,
-        new_account: AccountId,
+        account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleGranted {
+        role_type: RoleType,
+        account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleRevoked {
+        role_type: RoleType,
+        account: AccountId,
     }
 
     #[ink(storage)]
@@ -93,8 +195,16 @@ pub mod registry {
         pub agents: Vec<Agent>,
         // Sum of nomination agent relative weights
         pub total_weight: u64,
-        // Permissions for adding agents, updating weights, and removing agents
-        pub roles: Mapping<RoleType, Role>,
+        // Members of each role, see `RoleType`
+        pub access_control: AccessControl,
+        // Operations which are currently paused
+        pub paused: Mapping<PauseType, ()>,
+        // Pending upgrades of registry and agent logic
+        pub code_announcements: Mapping<CodeTarget, CodeAnnouncement>,
+        // Time between announcing and executing an upgrade
+        pub code_delay: u64,
+        // Pending decrease of `code_delay`
+        pub code_delay_proposal: Option<CodeDelayProposal>,
         // Used for instantiating agents
         pub vault: AccountId,
         pub nomination_agent_hash: Hash,
@@ -107,7 +217,11 @@ pub mod registry {
             Self {
                 agents: Vec::new(),
                 total_weight: 0,
-                roles: Mapping::default(),
+                access_control: AccessControl::default(),
+                paused: Mapping::default(),
+                code_announcements: Mapping::default(),
+                code_delay: MIN_CODE_DELAY,
+                code_delay_proposal: None,
                 vault: Self::env().caller(),
                 nomination_agent_hash: Hash::default(),
                 nomination_agent_counter: 0,
@@ -115,52 +229,73 @@ pub mod registry {
         }
 
         #[ink(constructor)]
-        pub fn new(
-            role_add: AccountId,
-            role_update: AccountId,
-            role_remove: AccountId,
-            role_set_code_hash: AccountId,
-            nomination_agent_hash: Hash,
-        ) -> Self {
-            let mut initial_roles = Mapping::default();
-            initial_roles.insert(
+        pub fn new(admin: AccountId, nomination_agent_hash: Hash) -> Self {
+            let mut access_control = AccessControl::default();
+            for role_type in [
+                RoleType::Admin,
                 RoleType::AddAgent,
-                &Role {
-                    admin: role_add,
-                    account: role_add,
-                },
-            );
-            initial_roles.insert(
                 RoleType::UpdateAgents,
-                &Role {
-                    admin: role_update,
-                    account: role_update,
-                },
-            );
-            initial_roles.insert(
                 RoleType::RemoveAgent,
-                &Role {
-                    admin: role_remove,
-                    account: role_remove,
-                },
-            );
-            initial_roles.insert(
                 RoleType::SetCodeHash,
-                &Role {
-                    admin: role_set_code_hash,
-                    account: role_set_code_hash,
-                },
-            );
+                RoleType::Pause,
+            ] {
+                access_control
+                    .grant(role_type as RoleId, admin)
+                    .expect("Role is granted once");
+            }
 
             Self {
                 agents: Vec::new(),
                 total_weight: 0,
-                roles: initial_roles,
+                access_control,
+                paused: Mapping::default(),
+                code_announcements: Mapping::default(),
+                code_delay: MIN_CODE_DELAY,
+                code_delay_proposal: None,
                 vault: Self::env().caller(),
                 nomination_agent_hash,
                 nomination_agent_counter: 0,
             }
         }
+
+        /// Shortest allowed `code_delay`, read from the Vault which knows the unlock cooldown
+        fn min_code_delay(&self) -> u64 {
+            build_call::<Environment>()
+                .call(self.vault)
+                .exec_input(ExecutionInput::new(Selector::new(ink::selector_bytes!(
+                    "IVault::get_min_code_delay"
+                ))))
+                .returns::<u64>()
+                .invoke()
+        }
+
+        /// Ensures the caller is a member of the role
+        fn ensure_role(&self, role_type: RoleType) -> Result<(), RegistryError> {
+            if !self.access_control.has_role(role_type as RoleId, Self::env().caller()) {
+                return Err(RegistryError::InvalidPermissions);
+            }
+            Ok(())
+        }
+
+        /// Removes the pending upgrade for the target
+        /// ensuring it matches `code_hash` and its delay has passed
+        fn consume_code_announcement(
+            &mut self,
+            target: CodeTarget,
+            code_hash: [u8; 32],
+        ) -> Result<(), RegistryError> {
+            let announcement = self
+                .code_announcements
+                .take(target)
+                .ok_or(RegistryError::NoCodeAnnouncement)?;
+            if announcement.code_hash != code_hash {
+                return Err(RegistryError::CodeHashMismatch);
+            }
+            if Self::env().block_timestamp() < announcement.execute_time {
+                return Err(RegistryError::CodeTimelock);
+            }
+            Ok(())
+        }
     }
 
     impl IRegistry for Registry {
@@ -174,11 +309,11 @@ pub mod registry {
             admin: AccountId,
             validator: AccountId,
         ) -> Result<AccountId, RegistryError> {
-            let caller = Self::env().caller();
             let nominator_bond = Self::env().transferred_value();
 
-            if caller != self.roles.get(RoleType::AddAgent).unwrap().account {
-                return Err(RegistryError::InvalidPermissions);
+            self.ensure_role(RoleType::AddAgent)?;
+            if self.paused.contains(PauseType::AddAgent) {
+                return Err(RegistryError::Paused);
             }
 
             let nomination_agent_counter = self.nomination_agent_counter; // shadow
@@ -218,11 +353,7 @@ pub mod registry {
             agents: Vec<AccountId>,
             new_weights: Vec<u64>,
         ) -> Result<(), RegistryError> {
-            let caller = Self::env().caller();
-
-            if caller != self.roles.get(RoleType::UpdateAgents).unwrap().account {
-                return Err(RegistryError::InvalidPermissions);
-            }
+            self.ensure_role(RoleType::UpdateAgents)?;
 
             if agents.len() != new_weights.len() {
                 return Err(RegistryError::InvalidInput);
@@ -259,11 +390,7 @@ pub mod registry {
         /// Agent must have no AZERO unbonding.
         #[ink(message, selector = 3)]
         fn remove_agent(&mut self, agent: AccountId) -> Result<(), RegistryError> {
-            let caller = Self::env().caller();
-
-            if caller != self.roles.get(RoleType::RemoveAgent).unwrap().account {
-                return Err(RegistryError::InvalidPermissions);
-            }
+            self.ensure_role(RoleType::RemoveAgent)?;
 
             if let Some(index) = self.agents.iter().position(|a| a.address == agent) {
                 let mut agent_contract: contract_ref!(INominationAgent) = agent.into();
@@ -296,100 +423,296 @@ pub mod registry {
             (self.total_weight, self.agents.clone())
         }
 
+        /// Replaces the validators nominated by an existing nomination agent
+        ///
+        /// Caller must have the UpdateAgents role.
+        /// Validators are checked by the agent, see `NominationAgent::set_validators`.
+        #[ink(message)]
+        fn update_validators(&mut self, agent: AccountId, validators: Vec<AccountId>) -> Result<(), RegistryError> {
+            self.ensure_role(RoleType::UpdateAgents)?;
+
+            if !self.agents.iter().any(|a| a.address == agent) {
+                return Err(RegistryError::AgentNotFound);
+            }
+
+            let mut agent_contract: contract_ref!(INominationAgent) = agent.into();
+            agent_contract
+                .set_validators(validators.clone())
+                .map_err(|_| RegistryError::InvalidValidators)?;
+
+            Self::env().emit_event(ValidatorsUpdated { agent, validators });
+
+            Ok(())
+        }
+
+        /// Returns the validators nominated by a nomination agent
+        #[ink(message)]
+        fn get_validators(&self, agent: AccountId) -> Result<Vec<AccountId>, RegistryError> {
+            if !self.agents.iter().any(|a| a.address == agent) {
+                return Err(RegistryError::AgentNotFound);
+            }
+
+            let agent_contract: contract_ref!(INominationAgent) = agent.into();
+            Ok(agent_contract.get_validators())
+        }
+
         /// ================================ Update Role Methods ================================
 
-        /// Transfers role to a new account
+        /// Proposes granting role to an account
         ///
-        /// Caller must be the admin for the role
+        /// Caller must have the Admin role.
+        /// The account must call `accept_role` to become a member.
         #[ink(message)]
-        fn transfer_role(
-            &mut self,
-            role_type: RoleType,
-            new_account: AccountId,
-        ) -> Result<(), RegistryError> {
-            if let Some(mut role) = self.roles.get(role_type.clone()) {
-                if Self::env().caller() != role.admin {
-                    return Err(RegistryError::InvalidPermissions);
-                }
-                if role.account == new_account {
-                    return Err(RegistryError::NoChange);
-                }
+        fn propose_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), RegistryError> {
+            self.access_control
+                .propose_role(Self::env().caller(), role_type as RoleId, account)?;
 
-                // Update role account
-                role.account = new_account;
-                self.roles.insert(role_type.clone(), &role);
+            Self::env().emit_event(RoleGrantProposed { role_type, account });
 
-                Self::env().emit_event(RoleAccountChanged {
-                    role_type,
-                    new_account,
-                });
-            } else {
-                return Err(RegistryError::InvalidRole);
+            Ok(())
+        }
+
+        /// Completes a role grant
+        ///
+        /// Caller must be the proposed account.
+        #[ink(message)]
+        fn accept_role(&mut self, role_type: RoleType) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            self.access_control.accept_role(caller, role_type as RoleId)?;
+
+            Self::env().emit_event(RoleGranted {
+                role_type,
+                account: caller,
+            });
+
+            Ok(())
+        }
+
+        /// Cancels a pending role grant
+        ///
+        /// Caller must have the Admin role.
+        #[ink(message)]
+        fn cancel_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), RegistryError> {
+            self.access_control
+                .cancel_role(Self::env().caller(), role_type as RoleId, account)?;
+
+            Self::env().emit_event(RoleGrantCancelled { role_type, account });
+
+            Ok(())
+        }
+
+        /// Removes an account from a role
+        ///
+        /// Caller must have the Admin role.
+        #[ink(message)]
+        fn revoke_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), RegistryError> {
+            self.access_control
+                .revoke_role(Self::env().caller(), role_type as RoleId, account)?;
+
+            Self::env().emit_event(RoleRevoked { role_type, account });
+
+            Ok(())
+        }
+
+        /// Removes the caller from a role
+        #[ink(message)]
+        fn renounce_role(&mut self, role_type: RoleType) -> Result<(), RegistryError> {
+            let caller = Self::env().caller();
+
+            self.access_control.renounce_role(caller, role_type as RoleId)?;
+
+            Self::env().emit_event(RoleRevoked {
+                role_type,
+                account: caller,
+            });
+
+            Ok(())
+        }
+
+        // ================================ Pause Methods ================================
+
+        /// Pauses an operation
+        ///
+        /// Caller must have the Pause role.
+        #[ink(message)]
+        fn pause(&mut self, pause_type: PauseType) -> Result<(), RegistryError> {
+            self.ensure_role(RoleType::Pause)?;
+            if self.paused.contains(pause_type.clone()) {
+                return Err(RegistryError::NoChange);
             }
 
+            self.paused.insert(pause_type.clone(), &());
+
+            Self::env().emit_event(Paused { pause_type });
+
             Ok(())
         }
 
-        /// Transfers administration of role to a new account
+        /// Unpauses an operation
         ///
-        /// Caller must be the admin for the role
+        /// Caller must have the Admin role.
         #[ink(message)]
-        fn transfer_role_admin(
-            &mut self,
-            role_type: RoleType,
-            new_account: AccountId,
-        ) -> Result<(), RegistryError> {
-            if let Some(mut role) = self.roles.get(role_type.clone()) {
-                if Self::env().caller() != role.admin {
-                    return Err(RegistryError::InvalidPermissions);
-                }
-                if role.admin == new_account {
-                    return Err(RegistryError::NoChange);
-                }
+        fn unpause(&mut self, pause_type: PauseType) -> Result<(), RegistryError> {
+            self.ensure_role(RoleType::Admin)?;
+            if !self.paused.contains(pause_type.clone()) {
+                return Err(RegistryError::NoChange);
+            }
 
-                // Update role admin
-                role.admin = new_account;
-                self.roles.insert(role_type.clone(), &role);
+            self.paused.remove(pause_type.clone());
 
-                Self::env().emit_event(RoleAdminChanged {
-                    role_type,
-                    new_account,
-                });
+            Self::env().emit_event(Unpaused { pause_type });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn is_paused(&self, pause_type: PauseType) -> bool {
+            self.paused.contains(pause_type)
+        }
+
+        /// ================================ Code Hash Methods ================================
+
+        /// Announces an upgrade to be executed after `code_delay`
+        ///
+        /// Caller must have the SetCodeHash role.
+        /// Replaces any pending announcement for the target.
+        #[ink(message)]
+        fn announce_code(&mut self, target: CodeTarget, code_hash: [u8; 32]) -> Result<(), RegistryError> {
+            self.ensure_role(RoleType::SetCodeHash)?;
+
+            let execute_time = Self::env().block_timestamp() + self.code_delay.max(self.min_code_delay());
+            self.code_announcements.insert(
+                target.clone(),
+                &CodeAnnouncement {
+                    code_hash,
+                    execute_time,
+                },
+            );
+
+            Self::env().emit_event(CodeAnnounced {
+                target,
+                code_hash,
+                execute_time,
+            });
+
+            Ok(())
+        }
+
+        /// Cancels a pending upgrade
+        ///
+        /// Caller must have the SetCodeHash role.
+        #[ink(message)]
+        fn cancel_code(&mut self, target: CodeTarget) -> Result<(), RegistryError> {
+            self.ensure_role(RoleType::SetCodeHash)?;
+
+            let announcement = self
+                .code_announcements
+                .take(target.clone())
+                .ok_or(RegistryError::NoCodeAnnouncement)?;
+
+            Self::env().emit_event(CodeAnnouncementCancelled {
+                target,
+                code_hash: announcement.code_hash,
+            });
+
+            Ok(())
+        }
+
+        /// Updates the delay between announcing and executing an upgrade
+        ///
+        /// Caller must have the SetCodeHash role.
+        /// Cannot be lower than the Vault's minimum code delay.
+        /// Increases apply immediately and drop any pending decrease.
+        /// Decreases are proposed and executed via `execute_code_delay` after the current `code_delay`.
+        #[ink(message)]
+        fn adjust_code_delay(&mut self, new_delay: u64) -> Result<(), RegistryError> {
+            self.ensure_role(RoleType::SetCodeHash)?;
+            if self.code_delay == new_delay {
+                return Err(RegistryError::NoChange);
+            }
+            if new_delay < self.min_code_delay() {
+                return Err(RegistryError::InvalidInput);
+            }
+
+            if new_delay > self.code_delay {
+                self.code_delay = new_delay;
+                self.code_delay_proposal = None;
+
+                Self::env().emit_event(CodeDelayAdjusted { new_delay });
             } else {
-                return Err(RegistryError::InvalidRole);
+                let execute_time = Self::env().block_timestamp() + self.code_delay;
+                self.code_delay_proposal = Some(CodeDelayProposal {
+                    new_delay,
+                    execute_time,
+                });
+
+                Self::env().emit_event(CodeDelayProposed {
+                    new_delay,
+                    execute_time,
+                });
             }
 
             Ok(())
         }
 
-        /// ================================ Code Hash Methods ================================
+        /// Decreases the code delay to the pending proposal
+        ///
+        /// Caller must have the SetCodeHash role.
+        /// Proposal must have been pending for at least the `code_delay` in effect when it was made.
+        #[ink(message)]
+        fn execute_code_delay(&mut self) -> Result<(), RegistryError> {
+            self.ensure_role(RoleType::SetCodeHash)?;
+
+            let proposal = self
+                .code_delay_proposal
+                .take()
+                .ok_or(RegistryError::NoCodeDelayProposal)?;
+            if Self::env().block_timestamp() < proposal.execute_time {
+                return Err(RegistryError::CodeTimelock);
+            }
+            if proposal.new_delay < self.min_code_delay() {
+                return Err(RegistryError::InvalidInput);
+            }
+
+            self.code_delay = proposal.new_delay;
+
+            Self::env().emit_event(CodeDelayAdjusted {
+                new_delay: proposal.new_delay,
+            });
+
+            Ok(())
+        }
 
         /// "Upgrade" the Registry contract logic
         ///
         /// Caller must have the SetCodeHash role.
+        /// Code hash must have been announced at least `code_delay` ago.
         #[ink(message)]
         fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), RegistryError> {
-            let caller = Self::env().caller();
+            self.ensure_role(RoleType::SetCodeHash)?;
 
-            if caller != self.roles.get(RoleType::SetCodeHash).unwrap().account {
-                return Err(RegistryError::InvalidPermissions);
-            }
+            self.consume_code_announcement(CodeTarget::Registry, code_hash)?;
 
             ink::env::set_code_hash(&code_hash)?;
 
+            Self::env().emit_event(CodeUpdated {
+                target: CodeTarget::Registry,
+                code_hash,
+            });
+
             Ok(())
         }
 
         /// "Upgrade" the logic of all nomination agent contracts
         ///
         /// Caller must have the SetCodeHash role.
+        /// Code hash must have been announced at least `code_delay` ago.
         #[ink(message)]
         fn set_agent_code(&mut self, nomination_agent_hash: [u8; 32]) -> Result<(), RegistryError> {
-            let caller = Self::env().caller();
+            self.ensure_role(RoleType::SetCodeHash)?;
 
-            if caller != self.roles.get(RoleType::SetCodeHash).unwrap().account {
-                return Err(RegistryError::InvalidPermissions);
-            }
+            self.consume_code_announcement(CodeTarget::Agent, nomination_agent_hash)?;
 
             for agent in self.agents.iter() {
                 let mut agent_contract: contract_ref!(INominationAgent) = agent.address.into();
@@ -400,19 +723,44 @@ pub mod registry {
 
             self.nomination_agent_hash = Hash::from(nomination_agent_hash);
 
+            Self::env().emit_event(CodeUpdated {
+                target: CodeTarget::Agent,
+                code_hash: nomination_agent_hash,
+            });
+
             Ok(())
         }
 
+        #[ink(message)]
+        fn get_code_announcement(&self, target: CodeTarget) -> Option<CodeAnnouncement> {
+            self.code_announcements.get(target)
+        }
+
+        #[ink(message)]
+        fn get_code_delay(&self) -> u64 {
+            self.code_delay
+        }
+
+        #[ink(message)]
+        fn get_code_delay_proposal(&self) -> Option<CodeDelayProposal> {
+            self.code_delay_proposal.clone()
+        }
+
         /// ================================ View Only Role Methods ================================
 
         #[ink(message)]
-        fn get_role(&self, role_type: RoleType) -> AccountId {
-            self.roles.get(role_type).unwrap().account
+        fn has_role(&self, role_type: RoleType, account: AccountId) -> bool {
+            self.access_control.has_role(role_type as RoleId, account)
+        }
+
+        #[ink(message)]
+        fn get_role_members(&self, role_type: RoleType) -> Vec<AccountId> {
+            self.access_control.get_role_members(role_type as RoleId)
         }
 
         #[ink(message)]
-        fn get_role_admin(&self, role_type: RoleType) -> AccountId {
-            self.roles.get(role_type).unwrap().admin
+        fn is_pending_role(&self, role_type: RoleType, account: AccountId) -> bool {
+            self.access_control.is_pending(role_type as RoleId, account)
         }
     }
 }
@@ -1,5 +1,5 @@
 use crate::errors::RegistryError;
-use crate::registry::{Agent, RoleType};
+use crate::registry::{Agent, CodeAnnouncement, CodeDelayProposal, CodeTarget, PauseType, RoleType};
 use ink::{primitives::AccountId, prelude::vec::Vec};
 
 #[ink::trait_definition]
@@ -20,20 +20,46 @@ pub trait IRegistry {
     fn remove_agent(&mut self, account: AccountId) -> Result<(), RegistryError>;
     #[ink(message, selector = 4)]
     fn get_agents(&self) -> (u64, Vec<Agent>);
+    #[ink(message)]
+    fn update_validators(&mut self, agent: AccountId, validators: Vec<AccountId>) -> Result<(), RegistryError>;
+    #[ink(message)]
+    fn get_validators(&self, agent: AccountId) -> Result<Vec<AccountId>, RegistryError>;
 
     #[ink(message)]
-    fn transfer_role(
-        &mut self,
-        role_type: RoleType,
-        new_account: AccountId,
-    ) -> Result<(), RegistryError>;
+    fn propose_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), RegistryError>;
 
     #[ink(message)]
-    fn transfer_role_admin(
-        &mut self,
-        role_type: RoleType,
-        new_account: AccountId,
-    ) -> Result<(), RegistryError>;
+    fn accept_role(&mut self, role_type: RoleType) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn cancel_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn revoke_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn renounce_role(&mut self, role_type: RoleType) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn pause(&mut self, pause_type: PauseType) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn unpause(&mut self, pause_type: PauseType) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn is_paused(&self, pause_type: PauseType) -> bool;
+
+    #[ink(message)]
+    fn announce_code(&mut self, target: CodeTarget, code_hash: [u8; 32]) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn cancel_code(&mut self, target: CodeTarget) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn adjust_code_delay(&mut self, new_delay: u64) -> Result<(), RegistryError>;
+
+    #[ink(message)]
+    fn execute_code_delay(&mut self) -> Result<(), RegistryError>;
 
     #[ink(message)]
     fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), RegistryError>;
@@ -42,8 +68,20 @@ pub trait IRegistry {
     fn set_agent_code(&mut self, nomination_agent_hash: [u8; 32]) -> Result<(), RegistryError>;
 
     #[ink(message)]
-    fn get_role(&self, role_type: RoleType) -> AccountId;
+    fn get_code_announcement(&self, target: CodeTarget) -> Option<CodeAnnouncement>;
+
+    #[ink(message)]
+    fn get_code_delay(&self) -> u64;
+
+    #[ink(message)]
+    fn get_code_delay_proposal(&self) -> Option<CodeDelayProposal>;
+
+    #[ink(message)]
+    fn has_role(&self, role_type: RoleType, account: AccountId) -> bool;
+
+    #[ink(message)]
+    fn get_role_members(&self, role_type: RoleType) -> Vec<AccountId>;
 
     #[ink(message)]
-    fn get_role_admin(&self, role_type: RoleType) -> AccountId;
+    fn is_pending_role(&self, role_type: RoleType, account: AccountId) -> bool;
 }
@@ -0,0 +1,167 @@
+use crate::errors::PSP34Error;
+use ink::{
+    prelude::vec::Vec,
+    primitives::AccountId,
+    storage::Mapping,
+};
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum Id {
+    U8(u8),
+    U16(u16),
+    U32(u32),
+    U64(u64),
+    U128(u128),
+    Bytes(Vec<u8>),
+}
+
+pub enum PSP34Event {
+    Transfer {
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        id: Id,
+    },
+    Approval {
+        owner: AccountId,
+        operator: AccountId,
+        id: Option<Id>,
+        approved: bool,
+    },
+}
+
+/// (owner, operator, token) where a `None` token approves the operator for all tokens of the owner
+type Approval = (AccountId, AccountId, Option<Id>);
+
+/// Minimal PSP34 storage and logic
+/// Each method returns the events which must be emitted by the contract
+#[ink::storage_item]
+#[derive(Debug, Default)]
+pub struct PSP34Data {
+    token_owner: Mapping<Id, AccountId>,
+    owned_tokens_count: Mapping<AccountId, u32>,
+    operator_approvals: Mapping<Approval, ()>,
+    total_supply: u128,
+}
+
+impl PSP34Data {
+    pub fn total_supply(&self) -> u128 {
+        self.total_supply
+    }
+
+    pub fn balance_of(&self, owner: AccountId) -> u32 {
+        self.owned_tokens_count.get(owner).unwrap_or_default()
+    }
+
+    pub fn owner_of(&self, id: &Id) -> Option<AccountId> {
+        self.token_owner.get(id)
+    }
+
+    /// Operator is approved for `id` or for all tokens of `owner`
+    pub fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<&Id>) -> bool {
+        self.operator_approvals.contains((owner, operator, None::<Id>))
+            || id.is_some() && self.operator_approvals.contains((owner, operator, id.cloned()))
+    }
+
+    pub fn approve(
+        &mut self,
+        caller: AccountId,
+        operator: AccountId,
+        id: Option<Id>,
+        approved: bool,
+    ) -> Result<Vec<PSP34Event>, PSP34Error> {
+        let mut owner = caller;
+
+        if let Some(id) = &id {
+            owner = self.owner_of(id).ok_or(PSP34Error::TokenNotExists)?;
+            if approved && owner == operator {
+                return Err(PSP34Error::SelfApprove);
+            }
+            if owner != caller && !self.allowance(owner, caller, None) {
+                return Err(PSP34Error::NotApproved);
+            }
+        }
+
+        if approved {
+            self.operator_approvals.insert((owner, operator, id.clone()), &());
+        } else {
+            self.operator_approvals.remove((owner, operator, id.clone()));
+        }
+
+        Ok(ink::prelude::vec![PSP34Event::Approval {
+            owner,
+            operator,
+            id,
+            approved,
+        }])
+    }
+
+    pub fn transfer(
+        &mut self,
+        caller: AccountId,
+        to: AccountId,
+        id: Id,
+    ) -> Result<Vec<PSP34Event>, PSP34Error> {
+        let owner = self.owner_of(&id).ok_or(PSP34Error::TokenNotExists)?;
+
+        if owner != caller && !self.allowance(owner, caller, Some(&id)) {
+            return Err(PSP34Error::NotApproved);
+        }
+
+        // Approvals for a single token do not survive the transfer
+        self.operator_approvals.remove((owner, caller, Some(id.clone())));
+        self.decrease_count(owner);
+        self.increase_count(to);
+        self.token_owner.insert(&id, &to);
+
+        Ok(ink::prelude::vec![PSP34Event::Transfer {
+            from: Some(owner),
+            to: Some(to),
+            id,
+        }])
+    }
+
+    pub fn mint(&mut self, to: AccountId, id: Id) -> Result<Vec<PSP34Event>, PSP34Error> {
+        if self.token_owner.contains(&id) {
+            return Err(PSP34Error::TokenExists);
+        }
+
+        self.increase_count(to);
+        self.token_owner.insert(&id, &to);
+        self.total_supply += 1;
+
+        Ok(ink::prelude::vec![PSP34Event::Transfer {
+            from: None,
+            to: Some(to),
+            id,
+        }])
+    }
+
+    pub fn burn(&mut self, id: Id) -> Result<Vec<PSP34Event>, PSP34Error> {
+        let owner = self.owner_of(&id).ok_or(PSP34Error::TokenNotExists)?;
+
+        self.decrease_count(owner);
+        self.token_owner.remove(&id);
+        self.total_supply -= 1;
+
+        Ok(ink::prelude::vec![PSP34Event::Transfer {
+            from: Some(owner),
+            to: None,
+            id,
+        }])
+    }
+
+    fn increase_count(&mut self, account: AccountId) {
+        let count = self.balance_of(account);
+        self.owned_tokens_count.insert(account, &(count + 1));
+    }
+
+    fn decrease_count(&mut self, account: AccountId) {
+        let count = self.balance_of(account);
+        if count > 1 {
+            self.owned_tokens_count.insert(account, &(count - 1));
+        } else {
+            self.owned_tokens_count.remove(account);
+        }
+    }
+}
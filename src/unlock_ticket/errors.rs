@@ -0,0 +1,18 @@
+use ink::prelude::string::String;
+
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PSP34Error {
+    /// Custom error type for implementation-based errors.
+    Custom(String),
+    /// Returned when an account which is not the owner of a token attempts to approve itself.
+    SelfApprove,
+    /// Returned if the caller is not allowed to perform an operation on the token.
+    NotApproved,
+    /// Returned if a token with the given id already exists.
+    TokenExists,
+    /// Returned if a token with the given id does not exist.
+    TokenNotExists,
+    /// Returned if safe transfer check fails.
+    SafeTransferCheckFailed(String),
+}
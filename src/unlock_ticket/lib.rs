@@ -0,0 +1,160 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+mod data;
+pub mod errors;
+mod traits;
+
+pub use data::Id;
+pub use traits::{UnlockTicket, PSP34};
+pub use crate::ticket::TicketRef;
+
+#[ink::contract]
+mod ticket {
+    use crate::data::{Id, PSP34Data, PSP34Event};
+    use crate::errors::PSP34Error;
+    use crate::traits::{UnlockTicket, PSP34};
+    use ink::prelude::{string::String, vec::Vec};
+
+    #[ink(storage)]
+    pub struct Ticket {
+        data: PSP34Data,
+        owner: AccountId,
+    }
+
+    impl Ticket {
+        #[ink(constructor)]
+        pub fn deploy_hash() -> Self {
+            Self {
+                data: PSP34Data::default(),
+                owner: Self::env().caller(),
+            }
+        }
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self {
+                data: PSP34Data::default(),
+                owner: Self::env().caller(),
+            }
+        }
+        #[ink(message)]
+        pub fn get_owner(&self) -> AccountId {
+            self.owner
+        }
+        // A helper function translating a vector of PSP34Events into the proper
+        // ink event types (defined internally in this contract) and emitting them.
+        fn emit_events(&self, events: Vec<PSP34Event>) {
+            for event in events {
+                match event {
+                    PSP34Event::Transfer { from, to, id } => {
+                        self.env().emit_event(Transfer { from, to, id })
+                    }
+                    PSP34Event::Approval {
+                        owner,
+                        operator,
+                        id,
+                        approved,
+                    } => self.env().emit_event(Approval {
+                        owner,
+                        operator,
+                        id,
+                        approved,
+                    }),
+                }
+            }
+        }
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        operator: AccountId,
+        #[ink(topic)]
+        id: Option<Id>,
+        approved: bool,
+    }
+
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        #[ink(topic)]
+        id: Id,
+    }
+
+    impl UnlockTicket for Ticket {
+        /// Mints a ticket representing an unlock request
+        ///
+        /// Restricted to the owner (vault)
+        #[ink(message, selector = 7777)]
+        fn mint(&mut self, to: AccountId, id: Id) -> Result<(), PSP34Error> {
+            if Self::env().caller() != self.owner {
+                return Err(PSP34Error::Custom(String::from("Caller is not Owner")));
+            }
+            let events = self.data.mint(to, id)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        /// Burns the ticket of a redeemed unlock request
+        ///
+        /// Restricted to the owner (vault)
+        #[ink(message, selector = 7778)]
+        fn burn(&mut self, id: Id) -> Result<(), PSP34Error> {
+            if Self::env().caller() != self.owner {
+                return Err(PSP34Error::Custom(String::from("Caller is not Owner")));
+            }
+            let events = self.data.burn(id)?;
+            self.emit_events(events);
+            Ok(())
+        }
+    }
+
+    impl PSP34 for Ticket {
+        #[ink(message)]
+        fn collection_id(&self) -> Id {
+            Id::Bytes(<_ as AsRef<[u8]>>::as_ref(&self.env().account_id()).to_vec())
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> u32 {
+            self.data.balance_of(owner)
+        }
+
+        #[ink(message)]
+        fn owner_of(&self, id: Id) -> Option<AccountId> {
+            self.data.owner_of(&id)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, operator: AccountId, id: Option<Id>) -> bool {
+            self.data.allowance(owner, operator, id.as_ref())
+        }
+
+        #[ink(message)]
+        fn approve(
+            &mut self,
+            operator: AccountId,
+            id: Option<Id>,
+            approved: bool,
+        ) -> Result<(), PSP34Error> {
+            let events = self.data.approve(self.env().caller(), operator, id, approved)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, id: Id, _data: Vec<u8>) -> Result<(), PSP34Error> {
+            let events = self.data.transfer(self.env().caller(), to, id)?;
+            self.emit_events(events);
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn total_supply(&self) -> u128 {
+            self.data.total_supply()
+        }
+    }
+}
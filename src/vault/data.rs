@@ -1,7 +1,11 @@
 use crate::errors::VaultError;
+use access_control::{AccessControl, RoleId};
 use crate::nomination_agent_utils::{
     call_compound,
     call_deposit,
+    call_payout,
+    call_rebond,
+    call_slash,
     call_unbond,
     call_withdraw_unbonded,
     query_staked_value,
@@ -29,44 +33,304 @@ pub type Timestamp = u64;
 pub const BIPS: u16 = 10000;
 pub const DAY: u64 = 86400 * 1000;
 pub const YEAR: u64 = DAY * 365_25 / 100; // https://docs.alephzero.org/aleph-zero/use/stake/staking-rewards
+pub const MAX_UNLOCK_REQUESTS_PAGE: u64 = 100;
+pub const MAX_FEE_RECIPIENTS: usize = 10;
+/// maximum eras claimed per agent in a single `payout_and_compound`
+pub const MAX_PAYOUT_ERAS: usize = 8;
+/// maximum protocol fee expressed in basis points
+pub const MAX_FEE: u16 = 20_00;
+/// maximum change of the protocol fee in a single proposal expressed in basis points
+pub const MAX_FEE_STEP: u16 = 10_00;
+/// minimum delay between proposing and executing a fee change
+pub const MIN_FEE_DELAY: u64 = DAY;
+/// maximum compound bounty expressed in basis points
+pub const MAX_COMPOUND_BOUNTY: u16 = 1_00;
+/// maximum AZERO paid as a single compound bounty
+pub const MAX_COMPOUND_BOUNTY_AZERO: Balance = 100_000_000_000_000; // 100 AZERO
+/// minimum delay between announcing and executing a code upgrade
+/// covers the 14 era bonding duration plus one batch interval so stakers can exit before an upgrade
+pub const MIN_CODE_DELAY: u64 = DAY * 15;
+/// smallest AZERO amount worth bonding to or unbonding from a single agent
+pub const MIN_AGENT_BOND_EXTRA: Balance = 100_000;
+pub const REFERRAL_PRECISION: u128 = 1_000_000_000_000_000_000;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum FeeMode {
+    /// `fee_percentage` of all shares per year
+    Management,
+    /// `fee_percentage` of AZERO compounded via `compound`
+    Performance,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum RoleType {
+    /// administers every role and unpauses operations, must remain the first variant
+    Admin,
+    /// adjusting fees
+    AdjustFee,
+    /// triggering fee distribution via `withdraw_fees`
+    FeeTo,
+    /// adjusting deposit and unlock limits, the buffer target, and the cooldown period
+    AdjustLimits,
+    /// pausing operations
+    PauseGuardian,
+    /// "upgrading" Vault logic via `set_code`, administered by itself once disabled
+    SetCode,
+    /// reporting validator slashes via `report_slash`
+    SlashReporter,
+    /// moving stake from over-allocated agents via `rebalance`
+    Rebalancer,
+}
+
+impl RoleType {
+    pub fn id(self) -> RoleId {
+        self as RoleId
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum PauseType {
+    /// staking via `stake` and its variants
+    Stake,
+    /// unlocking via `request_unlock`, `process_batch`, `instant_unlock`, and `cancel_unlock`
+    Unlock,
+    /// compounding via `compound`
+    Compound,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum UnlockStatus {
+    Pending,
+    Redeemed,
+    Cancelled,
+}
 
 #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
 pub struct UnlockRequest {
     pub creation_time: Timestamp,
-    pub azero: u128,
+    pub batch_id: u64,
+    pub shares: u128,
+    /// id of the PSP34 ticket representing ownership of the request
+    pub ticket_id: u128,
+    pub status: UnlockStatus,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct UnlockBatch {
+    /// sA0 queued for unlocking
+    pub total_shares: u128,
+    /// AZERO unbonded for the batch, set upon processing
+    pub total_azero: Balance,
+    /// time unbonding began, `None` until processed
+    pub process_time: Option<Timestamp>,
+    /// AZERO unbonded from each agent for the batch, set upon processing
+    pub agent_unbonds: Vec<(AccountId, Balance)>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct FeeProposal {
+    /// proposed fee percentage expressed in basis points
+    pub new_fee: u16,
+    /// earliest time the proposal can be executed
+    pub execute_time: Timestamp,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct FeeModeProposal {
+    /// proposed model for charging protocol fees
+    pub new_mode: FeeMode,
+    /// earliest time the proposal can be executed
+    pub execute_time: Timestamp,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct FeeDelayProposal {
+    /// proposed delay between proposing and executing a fee change
+    pub new_delay: u64,
+    /// earliest time the proposal can be executed
+    pub execute_time: Timestamp,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct CompoundBountyProposal {
+    /// proposed share of compounded AZERO expressed in basis points
+    pub new_bounty: u16,
+    /// proposed maximum AZERO paid as a single bounty
+    pub new_max_bounty: Balance,
+    /// proposed minimum time between bounty payouts
+    pub new_interval: u64,
+    /// earliest time the proposal can be executed
+    pub execute_time: Timestamp,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct CodeAnnouncement {
+    /// code hash the contract will be upgraded to
+    pub code_hash: [u8; 32],
+    /// earliest time the upgrade can be executed
+    pub execute_time: Timestamp,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct CodeDelayProposal {
+    /// proposed delay between announcing and executing an upgrade
+    pub new_delay: u64,
+    /// earliest time the proposal can be executed
+    pub execute_time: Timestamp,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Referrer {
+    /// sA0 minted to stakers referred by the referrer which has not been unlocked
+    pub referred_shares: u128,
+    /// `referred_shares * referral_reward_per_share` at the last settlement
+    pub reward_debt: u128,
+    /// settled referral rewards (sA0) which can be claimed
+    pub rewards: u128,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct ReferredStake {
+    /// referrer credited with the staker's referred shares
+    pub referrer: AccountId,
+    /// sA0 minted to the staker via referral which has not been unlocked
+    pub shares: u128,
 }
 
 #[ink::storage_item]
 #[derive(Debug)]
 pub struct VaultData {
-    /// account that can adjust fees
-    pub role_adjust_fee: AccountId,
-    /// account that receives the fees from `withdraw_fees`
-    pub role_fee_to: AccountId,
-    /// account that can "upgrade" Vault logic via `set_code`
-    pub role_set_code: Option<AccountId>,
-
-    /// total AZERO staked excluding AZERO being unbonded
+    /// members of each role, see `RoleType`
+    pub access_control: AccessControl,
+    /// pending upgrade of Vault logic
+    pub code_announcement: Option<CodeAnnouncement>,
+    /// time between announcing and executing an upgrade
+    pub code_delay: u64,
+    /// pending decrease of `code_delay`
+    pub code_delay_proposal: Option<CodeDelayProposal>,
+
+    /// operations which are currently paused
+    pub paused: Mapping<PauseType, ()>,
+
+    /// total AZERO staked excluding AZERO being unbonded for unlocks
+    /// includes idle AZERO held in the buffer (`buffer_reserve`) and AZERO being rebalanced (`total_rebalancing`)
     pub total_pooled: Balance,
     /// total sA0 minted
     pub total_shares_minted: u128,
     /// rolling accumulator of inflation fees (sA0) that can be minted and claimed by owner
     pub total_shares_virtual: u128,
+    /// rolling accumulator of inflation fees (sA0) reserved for referrers
+    pub total_shares_referral: u128,
+
+    /// share of accrued fees paid to referrers expressed in basis points
+    pub referral_fee_cut: u16,
+    /// record of each referrer indexed by referrer AccountId
+    pub referrers: Mapping<AccountId, Referrer>,
+    /// referrer and referred shares of each referred staker indexed by staker AccountId
+    pub referred_stakes: Mapping<AccountId, ReferredStake>,
+    /// total sA0 of referred stakers which has not been unlocked across all referrers
+    pub total_referred_shares: u128,
+    /// referral rewards (sA0) accrued per referred share scaled by `REFERRAL_PRECISION`
+    pub referral_reward_per_share: u128,
 
-    /// record of each user's unlock requests indexed by user AccountId
-    pub user_unlock_requests: Mapping<AccountId, Vec<UnlockRequest>>,
+    /// record of each user's unlock requests indexed by user AccountId and unlock id
+    pub user_unlock_requests: Mapping<(AccountId, u64), UnlockRequest>,
+    /// number of unlock requests created by each user, also the next unlock id
+    pub user_unlock_request_count: Mapping<AccountId, u64>,
+    /// record of unlock batches indexed by batch id
+    pub batch_unlock_requests: Mapping<u64, UnlockBatch>,
+    /// batch currently accepting unlock requests
+    pub current_batch_id: u64,
+    /// time the previous batch was processed
+    pub last_batch_process_time: Timestamp,
+    /// minimum time between processing of batches
+    pub batch_interval: u64,
 
-    /// time required to unbond staked funds
-    pub cooldown_period: u64,
+    /// duration of a staking era
+    pub era_duration: u64,
+    /// number of eras required to unbond staked funds (`bondingDuration`)
+    pub bonding_eras: u64,
 
-    /// last update time of claimable fees variable only modified by stake, redeem, withdraw_fees, and adjust_fee
+    /// accounts receiving protocol fees via `distribute_fees` with weights expressed in basis points
+    pub fee_recipients: Vec<(AccountId, u16)>,
+
+    /// last update time of claimable fees, updated before shares are minted or burned and before fee settings change
     pub last_fee_update: Timestamp,
-    /// annualized fee percentage expressed in basis points
+    /// fee percentage expressed in basis points, annualized or per compound depending on `fee_mode`
     pub fee_percentage: u16,
+    /// model used for charging protocol fees
+    pub fee_mode: FeeMode,
+    /// pending change of `fee_percentage`
+    pub fee_proposal: Option<FeeProposal>,
+    /// pending change of `fee_mode`
+    pub fee_mode_proposal: Option<FeeModeProposal>,
+    /// time between proposing and executing a fee or fee mode change
+    pub fee_delay: u64,
+    /// pending decrease of `fee_delay`
+    pub fee_delay_proposal: Option<FeeDelayProposal>,
+
+    /// minimum AZERO required to stake
+    pub minimum_stake: Balance,
+    /// minimum sA0 required to request an unlock
+    pub minimum_unlock: u128,
+
+    /// maximum AZERO which can be pooled via staking
+    pub max_total_pooled: Balance,
+    /// maximum sA0 a single account can hold after staking, `None` when unlimited
+    pub max_account_shares: Option<u128>,
+
+    /// target share of `total_pooled` kept unbonded in the Vault expressed in basis points
+    pub buffer_target: u16,
+    /// idle AZERO held by the Vault for instant unlocks
+    pub buffer_reserve: Balance,
+    /// fee charged on instant unlocks expressed in basis points, retained by the pool
+    pub instant_unlock_fee: u16,
+
+    /// AZERO being unbonded from over-allocated agents to be bonded to under-allocated agents
+    pub total_rebalancing: Balance,
+    /// time the in-flight rebalance began unbonding, `None` when no rebalance is in flight
+    pub rebalance_time: Option<Timestamp>,
+    /// AZERO unbonded from each agent for the in-flight rebalance, dropped once withdrawn into the Vault
+    pub rebalance_unbonds: Vec<(AccountId, Balance)>,
+    /// AZERO of the in-flight rebalance already withdrawn into the Vault, reserved until re-bonded
+    pub rebalance_withdrawn: Balance,
+
+    /// share of compounded AZERO paid to the caller of `compound` expressed in basis points
+    pub compound_bounty: u16,
+    /// maximum AZERO paid as a single compound bounty
+    pub max_compound_bounty: Balance,
+    /// minimum time between compound bounty payouts
+    pub compound_bounty_interval: u64,
+    /// time of the last compound bounty payout
+    pub last_compound_bounty: Timestamp,
+    /// pending increase of the compound bounty
+    pub compound_bounty_proposal: Option<CompoundBountyProposal>,
+
+    /// index of the agent following the last range compounded via `compound_range`
+    pub compound_cursor: u64,
+    /// index of the agent following the last range withdrawn via `withdraw_unbonded_range`
+    pub withdraw_cursor: u64,
 
     /// token contract used for representing protocol staked AZERO ownership
     pub shares_contract: AccountId,
+    /// NFT contract used for representing ownership of unlock requests
+    pub ticket_contract: AccountId,
+    /// id assigned to the next minted unlock ticket
+    pub next_ticket_id: u128,
     /// registry contract used for tracking nominator pools and weights
     pub registry_contract: RegistryRef,
 }
@@ -75,22 +339,79 @@ impl VaultData {
     pub fn new(
         admin: AccountId,
         shares_contract_: AccountId,
+        ticket_contract_: AccountId,
         registry_ref: RegistryRef,
         current_time: Timestamp,
         era: u64,
     ) -> VaultData {
+        let mut access_control = AccessControl::default();
+        for role_type in [
+            RoleType::Admin,
+            RoleType::AdjustFee,
+            RoleType::FeeTo,
+            RoleType::AdjustLimits,
+            RoleType::PauseGuardian,
+            RoleType::SetCode,
+            RoleType::SlashReporter,
+            RoleType::Rebalancer,
+        ] {
+            access_control
+                .grant(role_type.id(), admin)
+                .expect("Role is granted once");
+        }
+
         VaultData {
-            role_adjust_fee: admin,
-            role_fee_to: admin,
-            role_set_code: Some(admin),
+            access_control,
+            code_announcement: None,
+            code_delay: MIN_CODE_DELAY,
+            code_delay_proposal: None,
+            paused: Mapping::default(),
             total_pooled: 0,
             total_shares_minted: 0,
             total_shares_virtual: 0,
+            total_shares_referral: 0,
+            referral_fee_cut: 0,
+            referrers: Mapping::default(),
+            referred_stakes: Mapping::default(),
+            total_referred_shares: 0,
+            referral_reward_per_share: 0,
             user_unlock_requests: Mapping::default(),
-            cooldown_period: era * 14,
+            user_unlock_request_count: Mapping::default(),
+            batch_unlock_requests: Mapping::default(),
+            current_batch_id: 0,
+            last_batch_process_time: current_time,
+            batch_interval: era,
+            era_duration: era,
+            bonding_eras: 14,
             last_fee_update: current_time,
             fee_percentage: 2_00, // 2.00%
+            fee_mode: FeeMode::Management,
+            fee_proposal: None,
+            fee_mode_proposal: None,
+            fee_delay: DAY * 7,
+            fee_delay_proposal: None,
+            fee_recipients: [(admin, BIPS)].to_vec(),
+            minimum_stake: 1_000_000,
+            minimum_unlock: 0,
+            max_total_pooled: Balance::MAX,
+            max_account_shares: None,
+            buffer_target: 0,
+            buffer_reserve: 0,
+            instant_unlock_fee: 0,
+            total_rebalancing: 0,
+            rebalance_time: None,
+            rebalance_unbonds: Vec::new(),
+            rebalance_withdrawn: 0,
+            compound_bounty: 0,
+            max_compound_bounty: MAX_COMPOUND_BOUNTY_AZERO,
+            compound_bounty_interval: 0,
+            last_compound_bounty: 0,
+            compound_bounty_proposal: None,
+            compound_cursor: 0,
+            withdraw_cursor: 0,
             shares_contract: shares_contract_,
+            ticket_contract: ticket_contract_,
+            next_ticket_id: 0,
             registry_contract: registry_ref,
         }
     }
@@ -136,8 +457,32 @@ impl VaultData {
         (pos_diff, neg_diff, stakes, imbalances)
     }
 
+    pub fn has_role(&self, role_type: RoleType, account: AccountId) -> bool {
+        self.access_control.has_role(role_type.id(), account)
+    }
+
+    /// Time required to unbond staked funds
+    pub fn cooldown_period(&self) -> u64 {
+        self.era_duration * self.bonding_eras
+    }
+
+    /// Shortest allowed `code_delay`, long enough for an unlock requested
+    /// after an announcement to be batched and redeemed before the upgrade
+    pub fn min_code_delay(&self) -> u64 {
+        MIN_CODE_DELAY.max(self.cooldown_period() + self.batch_interval)
+    }
+
+    /// Smallest AZERO amount which can be split among all weighted agents
+    /// while giving each agent at least `MIN_AGENT_BOND_EXTRA`
+    pub fn get_minimum_bond_extra(&self) -> Balance {
+        let (_total_weight, agents) = self.registry_contract.get_agents();
+        let weighted_agents = agents.iter().filter(|a| a.weight > 0).count() as u128;
+        MIN_AGENT_BOND_EXTRA * weighted_agents
+    }
+
     /// Deposits a given amount to nominator agents splitting deposits by nominator weights and stake imbalances
     ///
+    /// The idle buffer is topped up to `buffer_target` before anything is deposited into agents.
     /// Uses a weighting algorithm that prioritizes negatively imbalanced (under-allocated) pools.
     /// Phase1: The amount is split among negatively imbalanced nodes according to their proportion of the total imbalance.
     /// Phase2: If the deposit amount is more than the negative imbalance, the remainder is split according to nominator weight proportions.
@@ -150,8 +495,44 @@ impl VaultData {
 
         let new_total_pooled = self.total_pooled + azero;
 
+        // Top up the buffer first
+        let buffer_target = self.pro_rata(new_total_pooled, self.buffer_target as u128, BIPS as u128);
+        let buffer_deficit = buffer_target.saturating_sub(self.buffer_reserve);
+        let buffer_amount = if azero < buffer_deficit { azero } else { buffer_deficit };
+        self.buffer_reserve += buffer_amount;
+
+        // Amount to deposit into agents
+        let azero = azero - buffer_amount;
+
+        if azero == 0 {
+            debug_println!("Buffered {} AZERO", buffer_amount);
+            self.total_pooled = new_total_pooled;
+            return Ok(());
+        }
+
+        self.deposit_into_agents(
+            &agents,
+            total_weight,
+            azero,
+            new_total_pooled - self.buffer_reserve - self.total_rebalancing,
+        )?;
+
+        self.total_pooled = new_total_pooled;
+
+        Ok(())
+    }
+
+    /// Deposits AZERO into agents, favouring under-allocated agents
+    /// `total_bonded` is the AZERO bonded across all agents once deposited
+    fn deposit_into_agents(
+        &self,
+        agents: &Vec<Agent>,
+        total_weight: u64,
+        azero: Balance,
+        total_bonded: Balance,
+    ) -> Result<(), VaultError> {
         let (_pos_diff, neg_diff, _stakes, imbalances) = self
-            .get_weight_imbalances(&agents, total_weight, new_total_pooled);
+            .get_weight_imbalances(agents, total_weight, total_bonded);
 
         // Amount to distribute to under-allocated agents
         let phase1 = if azero < neg_diff { azero } else { neg_diff };
@@ -163,11 +544,11 @@ impl VaultData {
         let mut deposit_amounts: Vec<u128> = Vec::with_capacity(n);
         let mut deposit_summation = 0;
 
-        for i in 0..n {
+        for (agent, imbalance) in agents.iter().zip(imbalances) {
             // Distribute to under-allocated agents
             // Weighted by agent imbalance
-            let phase1_amount = if imbalances[i] < 0 {
-                self.pro_rata(phase1, -imbalances[i] as u128, neg_diff)
+            let phase1_amount = if imbalance < 0 {
+                self.pro_rata(phase1, -imbalance as u128, neg_diff)
             } else {
                 0
             };
@@ -175,7 +556,7 @@ impl VaultData {
             // Distribute remaining amount equitably to all agents
             // Weighted by agent weight
             let phase2_amount = if phase2 > 0 {
-                self.pro_rata(phase2, agents[i].weight as u128, total_weight as u128)
+                self.pro_rata(phase2, agent.weight as u128, total_weight as u128)
             } else {
                 0
             };
@@ -196,12 +577,9 @@ impl VaultData {
         // Prioritizes agents added earlier in the registry
         // Fully allocates dust to the first agent which is receiving a deposit
         if dust > 0 {
-            for i in 0..n {
-                if deposit_amounts[i] > 0 {
-                    debug_println!("Allocating {} dust to agent #{}", dust, i);
-                    deposit_amounts[i] += dust;
-                    break;
-                }
+            if let Some((i, deposit_amount)) = deposit_amounts.iter_mut().enumerate().find(|(_, d)| **d > 0) {
+                debug_println!("Allocating {} dust to agent #{}", dust, i);
+                *deposit_amount += dust;
             }
         }
 
@@ -216,25 +594,44 @@ impl VaultData {
             }
         }
 
-        self.total_pooled = new_total_pooled;
-
         Ok(())
     }
 
     /// Unlocks a given amount of staked AZERO from the nominator pools
     ///
+    /// Any amount exceeding the bonded AZERO is taken from the idle buffer.
+    /// Fails with `RebalanceInProgress` when the remainder is held by an in-flight rebalance.
     /// Uses a weighting algorithm that prioritizes positively imbalanced (over-allocated) pools.
     /// Phase1: The amount is split among positively imbalanced nodes according to their proportion of the total imbalance.
     /// Phase2: If the unlock amount is more than the positive imbalance, the remainder is split according to nominator stake proportions.
-    pub fn delegate_unbonding(&mut self, azero: Balance) -> Result<(), VaultError> {
+    pub fn delegate_unbonding(&mut self, azero: Balance) -> Result<Vec<(AccountId, Balance)>, VaultError> {
         let (total_weight, agents) = self.registry_contract.get_agents();
 
         let total_pooled_ = self.total_pooled; // shadow
+        let total_bonded = total_pooled_ - self.buffer_reserve - self.total_rebalancing;
 
         let new_total_pooled = total_pooled_ - azero;
 
+        // AZERO being rebalanced cannot fund unlocks until it is bonded again
+        if azero > total_bonded + self.buffer_reserve {
+            return Err(VaultError::RebalanceInProgress);
+        }
+
+        // Draw from the buffer when bonded AZERO cannot cover the unlock
+        let azero = if azero > total_bonded {
+            debug_println!("Unlocking {} AZERO from buffer", azero - total_bonded);
+            self.buffer_reserve -= azero - total_bonded;
+            if total_bonded == 0 {
+                self.total_pooled = new_total_pooled;
+                return Ok(Vec::new());
+            }
+            total_bonded
+        } else {
+            azero
+        };
+
         let (pos_diff, _neg_diff, stakes, imbalances) = self
-            .get_weight_imbalances(&agents, total_weight, new_total_pooled);
+            .get_weight_imbalances(&agents, total_weight, total_bonded - azero);
 
         // Amount to withdraw from over-allocated agents
         let phase1 = if azero < pos_diff { azero } else { pos_diff };
@@ -242,7 +639,7 @@ impl VaultData {
         // Remaining amount to withdraw equitably from all agents
         let phase2 = azero - phase1;
 
-        let total_staked_after_phase1 = total_pooled_ - phase1;
+        let total_staked_after_phase1 = total_bonded - phase1;
 
         let n = agents.len();
         let mut unbond_amounts: Vec<u128> = Vec::with_capacity(n);
@@ -299,6 +696,7 @@ impl VaultData {
         }
 
         // Unbond
+        let mut agent_unbonds = Vec::new();
         for (i, a) in agents.iter().enumerate() {
             let unbond_amount = unbond_amounts[i];
             if unbond_amount > 0 {
@@ -306,29 +704,378 @@ impl VaultData {
                 if let Err(e) = call_unbond(a.address, unbond_amount) {
                     return Err(VaultError::InternalError(e));
                 }
+                agent_unbonds.push((a.address, unbond_amount));
             }
         }
 
         self.total_pooled = new_total_pooled;
 
-        Ok(())
+        Ok(agent_unbonds)
+    }
+
+    /// Rebond a portion of a processed batch back into the agents it was unbonded from
+    ///
+    /// Each agent rebonds its pro rata share of `shares` relative to the batch
+    /// AZERO of the portion which was drawn from the buffer is returned to the buffer
+    ///
+    /// # Returns
+    ///
+    /// `azero` - Total AZERO returned to the pool
+    pub fn delegate_rebonding(&mut self, batch: &mut UnlockBatch, shares: u128) -> Result<Balance, VaultError> {
+        let total_unbonding: Balance = batch.agent_unbonds.iter().map(|(_, unbonding)| unbonding).sum();
+
+        let mut azero = 0;
+        for (agent, unbonding) in batch.agent_unbonds.iter_mut() {
+            let rebond_amount = self.pro_rata(*unbonding, shares, batch.total_shares);
+            if rebond_amount > 0 {
+                debug_println!("Rebonding {} to agent {:?}", rebond_amount, agent);
+                if let Err(e) = call_rebond(*agent, rebond_amount) {
+                    return Err(VaultError::InternalError(e));
+                }
+                *unbonding -= rebond_amount;
+                azero += rebond_amount;
+            }
+        }
+
+        // Portion drawn from the buffer never left the Vault
+        let from_buffer = self.pro_rata(batch.total_azero - total_unbonding, shares, batch.total_shares);
+        self.buffer_reserve += from_buffer;
+        azero += from_buffer;
+
+        self.total_pooled += azero;
+
+        batch.total_azero -= azero;
+        batch.total_shares -= shares;
+
+        Ok(azero)
     }
 
     /// Claim all unbonded AZERO from the agents looping over each nominator pool
-    pub fn delegate_withdraw_unbonded(&self) -> Result<(), VaultError> {
+    pub fn delegate_withdraw_unbonded(&mut self, now: Timestamp) -> Result<(), VaultError> {
         let (_total_weight, agents) = self.registry_contract.get_agents();
 
-        for a in agents.into_iter() {
+        self.withdraw_unbonded_agents(&agents, now)
+    }
+
+    /// Claim unbonded AZERO from up to `count` agents beginning at index `start`
+    /// Advances `withdraw_cursor` past the range, wrapping to the first agent
+    ///
+    /// # Returns
+    ///
+    /// `cursor` - Index of the next agent to withdraw from
+    pub fn delegate_withdraw_unbonded_range(
+        &mut self,
+        start: u64,
+        count: u64,
+        now: Timestamp,
+    ) -> Result<u64, VaultError> {
+        let (_total_weight, agents) = self.registry_contract.get_agents();
+
+        let (agents, cursor) = Self::agent_range(agents, start, count)?;
+        self.withdraw_unbonded_agents(&agents, now)?;
+        self.withdraw_cursor = cursor;
+
+        Ok(cursor)
+    }
+
+    /// Claims unbonded AZERO from each agent
+    /// Once the in-flight rebalance has finished unbonding, its AZERO withdrawn from these agents
+    /// moves from `rebalance_unbonds` to `rebalance_withdrawn`
+    fn withdraw_unbonded_agents(&mut self, agents: &[Agent], now: Timestamp) -> Result<(), VaultError> {
+        for a in agents.iter() {
             if let Err(e) = call_withdraw_unbonded(a.address) {
                 return Err(VaultError::InternalError(e));
             }
         }
 
+        let rebalance_unbonded = match self.rebalance_time {
+            Some(t) => now >= t + self.cooldown_period(),
+            None => false,
+        };
+        if rebalance_unbonded {
+            let mut withdrawn = 0;
+            self.rebalance_unbonds.retain(|(agent, unbonded)| {
+                if agents.iter().any(|a| a.address == *agent) {
+                    withdrawn += *unbonded;
+                    false
+                } else {
+                    true
+                }
+            });
+            self.rebalance_withdrawn += withdrawn;
+        }
+
         Ok(())
     }
 
+    /// Selects up to `count` agents beginning at index `start`
+    ///
+    /// # Returns
+    ///
+    /// `agents` - Agents within the range
+    /// `cursor` - Index following the range, zero once the final agent is included
+    fn agent_range(agents: Vec<Agent>, start: u64, count: u64) -> Result<(Vec<Agent>, u64), VaultError> {
+        let n = agents.len() as u64;
+        if start >= n || count == 0 {
+            return Err(VaultError::InvalidAgentRange);
+        }
+
+        let end = start.saturating_add(count).min(n);
+        let cursor = if end == n { 0 } else { end };
+
+        let range = agents
+            .into_iter()
+            .skip(start as usize)
+            .take((end - start) as usize)
+            .collect();
+
+        Ok((range, cursor))
+    }
+
+    /// Lowers the stake and unbonding tracked by an agent to their real amounts after a validator slash
+    /// The staked loss is removed from `total_pooled`, lowering the value of all sA0 equally
+    /// The unbonding loss must be charged to the batches funded by the agent via `haircut_batches`
+    ///
+    /// # Returns
+    ///
+    /// `staked_slashed` - AZERO lost from the stake
+    /// `unbonding_slashed` - AZERO lost from unbonding
+    pub fn delegate_slash(
+        &mut self,
+        agent: AccountId,
+        staked: Balance,
+        unbonding: Balance,
+    ) -> Result<(Balance, Balance), VaultError> {
+        let (_total_weight, agents) = self.registry_contract.get_agents();
+
+        if !agents.iter().any(|a| a.address == agent) {
+            return Err(VaultError::AgentNotFound);
+        }
+
+        let (staked_slashed, unbonding_slashed) = call_slash(agent, staked, unbonding)
+            .map_err(VaultError::InternalError)?;
+
+        self.total_pooled = self.total_pooled.saturating_sub(staked_slashed);
+
+        Ok((staked_slashed, unbonding_slashed))
+    }
+
+    /// Charges AZERO slashed from an agent's unbonding funds to the batches it is unbonding for
+    /// Only batches still in their cooldown period hold funds in the agent's unlocking chunks
+    /// The loss is split pro rata to the AZERO each batch unbonded from the agent, lowering
+    /// the AZERO redeemable by every request in the batch
+    /// An in-flight rebalance shares the loss, which is removed from `total_pooled`
+    /// Haircuts never exceed the AZERO a batch unbonded from the agent
+    /// Any loss not charged to a batch or rebalance is removed from `total_pooled`
+    ///
+    /// # Returns
+    ///
+    /// `haircuts` - AZERO charged to each batch, by batch id
+    pub fn haircut_batches(&mut self, agent: AccountId, loss: Balance, now: Timestamp) -> Vec<(u64, Balance)> {
+        let cooldown_period = self.cooldown_period();
+
+        // Collect processed batches still unbonding from the agent, newest first
+        let mut unbonding_batches = Vec::new();
+        let mut total_unbonding = 0;
+        for batch_id in (0..self.current_batch_id).rev() {
+            let batch = match self.batch_unlock_requests.get(batch_id) {
+                Some(b) => b,
+                None => continue,
+            };
+            let process_time = match batch.process_time {
+                Some(t) => t,
+                None => continue,
+            };
+            if now >= process_time + cooldown_period {
+                break;
+            }
+            if let Some((_, unbonded)) = batch.agent_unbonds.iter().find(|(a, _)| *a == agent) {
+                if *unbonded > 0 {
+                    total_unbonding += *unbonded;
+                    unbonding_batches.push((batch_id, batch));
+                }
+            }
+        }
+
+        // Include the in-flight rebalance while it is unbonding from the agent
+        let rebalance_unbonded = match self.rebalance_time {
+            Some(t) if now < t + cooldown_period => self
+                .rebalance_unbonds
+                .iter()
+                .find(|(a, _)| *a == agent)
+                .map(|(_, unbonded)| *unbonded)
+                .unwrap_or(0),
+            _ => 0,
+        };
+        total_unbonding += rebalance_unbonded;
+
+        if total_unbonding == 0 {
+            self.total_pooled = self.total_pooled.saturating_sub(loss);
+            return Vec::new();
+        }
+
+        // Losses beyond the recorded unbonding cannot be charged to the batches
+        let chargeable = loss.min(total_unbonding);
+        if loss > chargeable {
+            self.total_pooled = self.total_pooled.saturating_sub(loss - chargeable);
+        }
+
+        let mut charged = 0;
+        if rebalance_unbonded > 0 {
+            let haircut = if unbonding_batches.is_empty() {
+                chargeable
+            } else {
+                self.pro_rata(chargeable, rebalance_unbonded, total_unbonding)
+            };
+            let entry = self.rebalance_unbonds.iter_mut().find(|(a, _)| *a == agent).unwrap();
+            entry.1 -= haircut;
+            self.total_rebalancing -= haircut;
+            self.total_pooled = self.total_pooled.saturating_sub(haircut);
+            charged += haircut;
+        }
+
+        let mut haircuts = Vec::new();
+        let n = unbonding_batches.len();
+        for (i, (batch_id, mut batch)) in unbonding_batches.into_iter().enumerate() {
+            let entry = batch.agent_unbonds.iter_mut().find(|(a, _)| *a == agent).unwrap();
+            let haircut = if i == n - 1 {
+                // Assign rounding dust to the last batch
+                chargeable - charged
+            } else {
+                self.pro_rata(chargeable, entry.1, total_unbonding)
+            }
+            .min(entry.1);
+            entry.1 -= haircut;
+            batch.total_azero -= haircut;
+            charged += haircut;
+
+            self.batch_unlock_requests.insert(batch_id, &batch);
+            haircuts.push((batch_id, haircut));
+        }
+
+        // Dust the last batch cannot absorb is socialized
+        if chargeable > charged {
+            self.total_pooled = self.total_pooled.saturating_sub(chargeable - charged);
+        }
+
+        haircuts
+    }
+
+    /// Unbonds AZERO from over-allocated agents to be bonded to under-allocated agents
+    /// Weighted by agent imbalance, capped by `max_amount`
+    /// The AZERO remains in `total_pooled` while unbonding, tracked by `total_rebalancing`
+    ///
+    /// # Returns
+    ///
+    /// `total_unbonded` - Total AZERO unbonded across all agents
+    pub fn delegate_rebalance(&mut self, max_amount: Balance, now: Timestamp) -> Result<Balance, VaultError> {
+        if self.rebalance_time.is_some() {
+            return Err(VaultError::RebalanceInProgress);
+        }
+
+        let (total_weight, agents) = self.registry_contract.get_agents();
+
+        let total_bonded = self.total_pooled - self.buffer_reserve;
+        let (pos_diff, _neg_diff, _stakes, imbalances) = self
+            .get_weight_imbalances(&agents, total_weight, total_bonded);
+
+        let azero = if max_amount < pos_diff { max_amount } else { pos_diff };
+
+        let mut rebalance_unbonds = Vec::new();
+        let mut total_unbonded = 0;
+        if azero > 0 {
+            for (i, a) in agents.iter().enumerate() {
+                if imbalances[i] <= 0 {
+                    continue;
+                }
+                let unbond_amount = self.pro_rata(azero, imbalances[i] as u128, pos_diff);
+                if unbond_amount > 0 {
+                    debug_println!("Rebalancing {} from agent #{}", unbond_amount, i);
+                    if let Err(e) = call_unbond(a.address, unbond_amount) {
+                        return Err(VaultError::InternalError(e));
+                    }
+                    rebalance_unbonds.push((a.address, unbond_amount));
+                    total_unbonded += unbond_amount;
+                }
+            }
+        }
+
+        if total_unbonded == 0 {
+            return Err(VaultError::ZeroRebalancing);
+        }
+
+        self.total_rebalancing = total_unbonded;
+        self.rebalance_time = Some(now);
+        self.rebalance_unbonds = rebalance_unbonds;
+
+        Ok(total_unbonded)
+    }
+
+    /// Claims the AZERO unbonded by the in-flight rebalance once the cooldown has passed
+    /// Bonds it to under-allocated agents
+    ///
+    /// # Returns
+    ///
+    /// `total_rebalanced` - Total AZERO bonded across all agents
+    pub fn delegate_complete_rebalance(&mut self, now: Timestamp) -> Result<Balance, VaultError> {
+        let rebalance_time = match self.rebalance_time {
+            Some(t) => t,
+            None => return Err(VaultError::NoRebalance),
+        };
+        if now < rebalance_time + self.cooldown_period() {
+            return Err(VaultError::CooldownPeriod);
+        }
+
+        for (agent, _) in self.rebalance_unbonds.iter() {
+            if let Err(e) = call_withdraw_unbonded(*agent) {
+                return Err(VaultError::InternalError(e));
+            }
+        }
+
+        let azero = self.total_rebalancing;
+        self.total_rebalancing = 0;
+        self.rebalance_time = None;
+        self.rebalance_unbonds = Vec::new();
+        self.rebalance_withdrawn = 0;
+
+        if azero > 0 {
+            let (total_weight, agents) = self.registry_contract.get_agents();
+            if total_weight == 0 {
+                return Err(VaultError::ZeroTotalWeight);
+            }
+            self.deposit_into_agents(&agents, total_weight, azero, self.total_pooled - self.buffer_reserve)?;
+        }
+
+        Ok(azero)
+    }
+
+    /// Claim staking rewards of each era for the validators of up to `count` agents beginning at index `start`
+    /// via `PayoutStakers`, bounding the calls made in a single transaction
+    /// Eras which cannot be claimed, such as those already claimed by anyone else, are skipped
+    ///
+    /// # Returns
+    ///
+    /// `payouts` - Number of successful payouts across the range
+    pub fn delegate_payout(&self, eras: &[u32], start: u64, count: u64) -> Result<u32, VaultError> {
+        let (_total_weight, agents) = self.registry_contract.get_agents();
+
+        let (agents, _cursor) = Self::agent_range(agents, start, count)?;
+
+        let mut payouts = 0;
+        for (i, a) in agents.iter().enumerate() {
+            for era in eras.iter() {
+                match call_payout(a.address, *era) {
+                    Ok(()) => payouts += 1,
+                    Err(e) => debug_println!("Skipping payout of era {} for agent #{}: {:?}", era, i, e),
+                }
+            }
+        }
+
+        Ok(payouts)
+    }
+
     /// Reinvest AZERO across all agents without issuing new shares
-    /// Rewards must have already been paid via `PayoutStakers`
+    /// Rewards must have already been paid via `PayoutStakers`, see `delegate_payout`
     ///
     /// # Returns
     ///
@@ -336,9 +1083,38 @@ impl VaultData {
     pub fn delegate_compound(&mut self) -> Result<Balance, VaultError> {
         let (_total_weight, agents) = self.registry_contract.get_agents();
 
+        let total_compounded = self.compound_agents(&agents)?;
+
+        if total_compounded == 0 {
+            return Err(VaultError::ZeroCompounding);
+        }
+
+        Ok(total_compounded)
+    }
+
+    /// Reinvest AZERO across up to `count` agents beginning at index `start`
+    /// Advances `compound_cursor` past the range, wrapping to the first agent
+    /// Agents without rewards do not cause a failure so keepers can always advance
+    ///
+    /// # Returns
+    ///
+    /// `total_compounded` - Total AZERO compounded across the range
+    /// `cursor` - Index of the next agent to compound
+    pub fn delegate_compound_range(&mut self, start: u64, count: u64) -> Result<(Balance, u64), VaultError> {
+        let (_total_weight, agents) = self.registry_contract.get_agents();
+
+        let (agents, cursor) = Self::agent_range(agents, start, count)?;
+        let total_compounded = self.compound_agents(&agents)?;
+        self.compound_cursor = cursor;
+
+        Ok((total_compounded, cursor))
+    }
+
+    /// Compounds each agent, adding the compounded AZERO to `total_pooled`
+    fn compound_agents(&mut self, agents: &[Agent]) -> Result<Balance, VaultError> {
         let mut total_compounded = 0;
 
-        for (i, a) in agents.into_iter().enumerate() {
+        for (i, a) in agents.iter().enumerate() {
             match call_compound(a.address) {
                 Ok(compound_amount) => {
                     debug_println!("Compounded {} to agent #{}", compound_amount, i);
@@ -348,17 +1124,13 @@ impl VaultData {
             }
         }
 
-        if total_compounded == 0 {
-            return Err(VaultError::ZeroCompounding);
-        }
-
         self.total_pooled += total_compounded;
 
         Ok(total_compounded)
     }
 
     /// Calculates summation of fees from last update until now
-    /// Must be called before changing: `total_shares_minted`, `fee_percentage`
+    /// Must be called before changing: `total_shares_minted`, `fee_percentage`, `fee_mode`, `referral_fee_cut`
     /// Must be called before calculating redemption ratio via: `get_shares_from_azero()` and `get_azero_from_shares()`
     pub fn update_fees(&mut self, current_time: Timestamp) {
         // Time since last update
@@ -366,36 +1138,225 @@ impl VaultData {
 
         // Calculate fee accumulation since last update
         if time > 0 {
-            let virtual_shares = self.pro_rata(
-                self.total_shares_minted + self.total_shares_virtual,
-                self.fee_percentage as u128,
-                BIPS as u128,
+            let fee_shares = self.get_accrued_fee_shares(time);
+            self.accrue_fees(fee_shares);
+            self.last_fee_update = current_time;
+        }
+    }
+
+    /// Distributes newly accrued fee shares between referrers and the protocol
+    pub fn accrue_fees(&mut self, fee_shares: u128) {
+        let (protocol_shares, referral_shares) = self.split_fees(fee_shares);
+
+        if referral_shares > 0 {
+            self.referral_reward_per_share += self.pro_rata(
+                referral_shares,
+                REFERRAL_PRECISION,
+                self.total_referred_shares,
             );
-            let time_weighted_virtual_shares = self.pro_rata(virtual_shares, time as u128, YEAR as u128);
+            self.total_shares_referral += referral_shares;
+        }
+        self.total_shares_virtual += protocol_shares;
+    }
 
-            self.total_shares_virtual += time_weighted_virtual_shares;
-            self.last_fee_update = current_time;
+    /// Calculates the bounty owed to the caller of `compound`
+    /// A `compound_bounty` cut of the compounded AZERO capped by `max_compound_bounty`
+    /// Nothing is owed within `compound_bounty_interval` of the previous payout
+    ///
+    /// # Returns
+    ///
+    /// `bounty_azero` - AZERO value of the bounty
+    /// `bounty_shares` - sA0 to be minted to the caller
+    pub fn charge_compound_bounty(&mut self, compounded: Balance, now: Timestamp) -> (Balance, u128) {
+        if self.compound_bounty == 0 || now < self.last_compound_bounty + self.compound_bounty_interval {
+            return (0, 0);
+        }
+
+        let bounty_azero = self
+            .pro_rata(compounded, self.compound_bounty as u128, BIPS as u128)
+            .min(self.max_compound_bounty);
+        let total_shares = self.total_shares_minted + self.total_shares_virtual + self.total_shares_referral;
+        if bounty_azero == 0 || total_shares == 0 {
+            return (0, 0);
         }
+
+        let bounty_shares = self.pro_rata(bounty_azero, total_shares, self.total_pooled - bounty_azero);
+        self.last_compound_bounty = now;
+
+        (bounty_azero, bounty_shares)
     }
 
-    /// Returns the virtual shares that will exist at the given time
-    pub fn get_virtual_shares_at_time(&self, current_time: Timestamp) -> Balance {
+    /// Charges the performance fee on newly compounded AZERO
+    /// Fee shares are priced at the redemption ratio excluding the fee itself
+    /// `update_fees` must be called first
+    ///
+    /// # Returns
+    ///
+    /// `fee_shares` - sA0 accrued as protocol fees, zero outside of `FeeMode::Performance`
+    pub fn charge_performance_fee(&mut self, compounded: Balance) -> u128 {
+        if self.fee_mode != FeeMode::Performance {
+            return 0;
+        }
+
+        let fee_azero = self.pro_rata(compounded, self.fee_percentage as u128, BIPS as u128);
+        let total_shares = self.total_shares_minted + self.total_shares_virtual + self.total_shares_referral;
+        if fee_azero == 0 || total_shares == 0 {
+            return 0;
+        }
+
+        let fee_shares = self.pro_rata(fee_azero, total_shares, self.total_pooled - fee_azero);
+        self.accrue_fees(fee_shares);
+
+        fee_shares
+    }
+
+    /// Time weighted fee shares accrued over `time` since the last update
+    /// Nothing accrues over time outside of `FeeMode::Management`
+    fn get_accrued_fee_shares(&self, time: u64) -> u128 {
+        if self.fee_mode != FeeMode::Management {
+            return 0;
+        }
+        let virtual_shares = self.pro_rata(
+            self.total_shares_minted + self.total_shares_virtual + self.total_shares_referral,
+            self.fee_percentage as u128,
+            BIPS as u128,
+        );
+        self.pro_rata(virtual_shares, time as u128, YEAR as u128)
+    }
+
+    /// Splits fee shares into the protocol and referrer portions
+    /// Referrers receive nothing until at least one referred stake exists
+    fn split_fees(&self, fee_shares: u128) -> (u128, u128) {
+        if self.total_referred_shares == 0 {
+            return (fee_shares, 0);
+        }
+        let referral_shares = self.pro_rata(fee_shares, self.referral_fee_cut as u128, BIPS as u128);
+        (fee_shares - referral_shares, referral_shares)
+    }
+
+    /// Returns the protocol and referral virtual shares that will exist at the given time
+    fn get_fee_shares_at_time(&self, current_time: Timestamp) -> (u128, u128) {
         // Time since last update
         let time = current_time - self.last_fee_update;
 
         if time > 0 {
             // Calculate fee accumulation since last update
-            let virtual_shares = self.pro_rata(
-                self.total_shares_minted + self.total_shares_virtual,
-                self.fee_percentage as u128,
-                BIPS as u128,
-            );
-            let time_weighted_virtual_shares = self.pro_rata(virtual_shares, time as u128, YEAR as u128);
-            self.total_shares_virtual + time_weighted_virtual_shares
+            let (protocol_shares, referral_shares) = self.split_fees(self.get_accrued_fee_shares(time));
+            (self.total_shares_virtual + protocol_shares, self.total_shares_referral + referral_shares)
         } else {
             // No additional fee accumulation is required
-            self.total_shares_virtual
+            (self.total_shares_virtual, self.total_shares_referral)
+        }
+    }
+
+    /// Returns the virtual shares that will exist at the given time
+    pub fn get_virtual_shares_at_time(&self, current_time: Timestamp) -> Balance {
+        self.get_fee_shares_at_time(current_time).0
+    }
+
+    /// Returns the unclaimed referral rewards that will exist at the given time
+    pub fn get_referral_shares_at_time(&self, current_time: Timestamp) -> Balance {
+        self.get_fee_shares_at_time(current_time).1
+    }
+
+    /// Moves a referrer's pending rewards into `rewards` at the current `referral_reward_per_share`
+    /// `update_fees` must be called first
+    pub fn settle_referrer(&self, referrer: &mut Referrer) {
+        let accumulated = self.pro_rata(referrer.referred_shares, self.referral_reward_per_share, REFERRAL_PRECISION);
+        referrer.rewards += accumulated - referrer.reward_debt;
+        referrer.reward_debt = accumulated;
+    }
+
+    /// Credits sA0 minted to a referred staker towards the referrer
+    /// A staker has a single referrer, a new referrer takes over the staker's referred shares
+    /// Callers must ensure only the staker changes its referrer
+    /// `update_fees` must be called first
+    pub fn add_referred_shares(&mut self, referral_id: AccountId, staker: AccountId, shares: u128) {
+        let (existing, credit) = match self.referred_stakes.get(staker) {
+            Some(s) if s.referrer != referral_id => {
+                self.change_referred_shares(s.referrer, 0, s.shares);
+                (s.shares, shares + s.shares)
+            },
+            Some(s) => (s.shares, shares),
+            None => (0, shares),
+        };
+        self.change_referred_shares(referral_id, credit, 0);
+
+        self.referred_stakes.insert(
+            staker,
+            &ReferredStake {
+                referrer: referral_id,
+                shares: existing + shares,
+            },
+        );
+    }
+
+    /// Stops crediting the referrer for sA0 unlocked by a referred staker
+    /// Unlocked shares are released from the staker's referred shares first
+    pub fn remove_referred_shares(&mut self, staker: AccountId, shares: u128, now: Timestamp) {
+        let mut stake = match self.referred_stakes.get(staker) {
+            Some(s) => s,
+            None => return,
+        };
+
+        // Settle rewards earned on the shares before they are released
+        self.update_fees(now);
+
+        let released = shares.min(stake.shares);
+        self.change_referred_shares(stake.referrer, 0, released);
+
+        stake.shares -= released;
+        if stake.shares == 0 {
+            self.referred_stakes.remove(staker);
+        } else {
+            self.referred_stakes.insert(staker, &stake);
+        }
+    }
+
+    /// Stops crediting the referrer for referred sA0 the staker no longer holds
+    /// Covers sA0 transferred away and unlocked from another account
+    pub fn cap_referred_shares(&mut self, staker: AccountId, balance: u128, now: Timestamp) {
+        let mut stake = match self.referred_stakes.get(staker) {
+            Some(s) if s.shares > balance => s,
+            _ => return,
+        };
+
+        // Settle rewards earned on the shares before they are released
+        self.update_fees(now);
+
+        self.change_referred_shares(stake.referrer, 0, stake.shares - balance);
+
+        stake.shares = balance;
+        if stake.shares == 0 {
+            self.referred_stakes.remove(staker);
+        } else {
+            self.referred_stakes.insert(staker, &stake);
+        }
+    }
+
+    fn change_referred_shares(&mut self, referral_id: AccountId, added: u128, removed: u128) {
+        let mut referrer = self.referrers.get(referral_id).unwrap_or_default();
+        self.settle_referrer(&mut referrer);
+        referrer.referred_shares = referrer.referred_shares + added - removed;
+        referrer.reward_debt = self.pro_rata(
+            referrer.referred_shares,
+            self.referral_reward_per_share,
+            REFERRAL_PRECISION,
+        );
+        self.referrers.insert(referral_id, &referrer);
+        self.total_referred_shares = self.total_referred_shares + added - removed;
+    }
+
+    /// Returns the referral rewards claimable by a referrer at the given time
+    pub fn get_referral_rewards_at_time(&self, referrer: &Referrer, current_time: Timestamp) -> u128 {
+        let time = current_time - self.last_fee_update;
+        let mut reward_per_share = self.referral_reward_per_share;
+        if time > 0 && self.total_referred_shares > 0 {
+            let (_, referral_shares) = self.split_fees(self.get_accrued_fee_shares(time));
+            reward_per_share += self.pro_rata(referral_shares, REFERRAL_PRECISION, self.total_referred_shares);
         }
+        let accumulated = self.pro_rata(referrer.referred_shares, reward_per_share, REFERRAL_PRECISION);
+        referrer.rewards + accumulated - referrer.reward_debt
     }
 
     /// Performs the u128 operations: a * b / c
@@ -1,7 +1,9 @@
 use ink::{
     prelude::string::String,
 };
+use access_control::errors::AccessControlError;
 use psp22::PSP22Error;
+use unlock_ticket::errors::PSP34Error;
 use crate::nomination_agent_utils::RuntimeError;
 
 #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -9,21 +11,52 @@ use crate::nomination_agent_utils::RuntimeError;
 pub enum VaultError {
     Duplication,
     InvalidPercent,
+    MaxFeeStep,
+    InvalidFeeDelay,
+    NoFeeDelayProposal,
+    NoFeeProposal,
+    FeeTimelock,
+    MaxCompoundBounty,
+    InvalidCodeDelay,
+    NoCodeDelayProposal,
+    NoCodeAnnouncement,
+    CodeTimelock,
+    CodeHashMismatch,
+    InvalidFeeRecipients,
     InvalidBatchUnlockRequest,
     InvalidUserUnlockRequest,
     CooldownPeriod,
+    InvalidCooldown,
+    UnbondingComplete,
     InvalidPermissions,
     NoChange,
+    Paused,
     ZeroDepositing,
     ZeroUnbonding,
     ZeroTotalWeight,
+    AgentNotFound,
+    InvalidAgentRange,
     ZeroCompounding,
+    MaxPayoutEras,
+    ZeroRebalancing,
+    RebalanceInProgress,
+    NoRebalance,
+    ZeroReferralRewards,
+    InvalidReferral,
     MinimumStake,
+    MinimumUnlock,
+    MinimumBondExtra,
+    MaxTotalPooled,
+    MaxAccountShares,
+    InsufficientBuffer,
+    Slippage,
     /// An interaction with ink! environment has failed
     // NOTE: We're representing the `ink::env::Error` as `String` b/c the
     // type does not have Encode/Decode implemented.
     InkEnvError(String),
     InternalError(RuntimeError),
     TokenError(PSP22Error),
+    TicketError(PSP34Error),
     InternalTokenError,
+    AccessControlError(AccessControlError),
 }
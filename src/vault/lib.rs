@@ -10,6 +10,7 @@ mod vault {
     use crate::errors::VaultError;
     use crate::traits::*;
 
+    use access_control::errors::AccessControlError;
     use ink::{
         codegen::EmitEvent,
         contract_ref,
@@ -21,6 +22,7 @@ mod vault {
     use psp22::{PSP22Burnable, PSP22};
     use registry::{registry::RegistryRef, traits::IRegistry};
     use share_token::{ShareToken, TokenRef};
+    use unlock_ticket::{Id, TicketRef, UnlockTicket, PSP34};
 
     /// Errors returned by the contract's methods.
     impl From<InkEnvError> for VaultError {
@@ -29,13 +31,21 @@ mod vault {
         }
     }
 
+    impl From<AccessControlError> for VaultError {
+        fn from(e: AccessControlError) -> Self {
+            VaultError::AccessControlError(e)
+        }
+    }
+
     /// Alias for wrapper around all events in this contract generated by ink!.
     type Event = <Vault as ContractEventBase>::Type;
 
     #[ink(event)]
     pub struct Staked {
         #[ink(topic)]
-        staker: AccountId,
+        payer: AccountId,
+        #[ink(topic)]
+        beneficiary: AccountId,
         azero: Balance,
         new_shares: u128,
         virtual_shares: u128,
@@ -52,39 +62,222 @@ mod vault {
         caller: AccountId,
         azero: Balance,
         virtual_shares: u128,
+        bounty: Balance,
+    }
+    #[ink(event)]
+    pub struct Slashed {
+        #[ink(topic)]
+        agent: AccountId,
+        azero: Balance,
+        unbonding_azero: Balance,
+    }
+    #[ink(event)]
+    pub struct BatchSlashed {
+        #[ink(topic)]
+        batch_id: u64,
+        #[ink(topic)]
+        agent: AccountId,
+        azero: Balance,
+    }
+    #[ink(event)]
+    pub struct RebalanceStarted {
+        caller: AccountId,
+        azero: Balance,
+    }
+    #[ink(event)]
+    pub struct RebalanceCompleted {
+        caller: AccountId,
+        azero: Balance,
     }
     #[ink(event)]
     pub struct UnlockRequested {
         #[ink(topic)]
         staker: AccountId,
-        unlock_id: u128,
+        unlock_id: u64,
+        batch_id: u64,
+        ticket_id: u128,
+        shares: u128,
+    }
+    #[ink(event)]
+    pub struct BatchUnlockProcessed {
+        #[ink(topic)]
+        batch_id: u64,
+        shares: u128,
+        azero: u128,
+        virtual_shares: u128,
+    }
+    #[ink(event)]
+    pub struct InstantUnlocked {
+        #[ink(topic)]
+        staker: AccountId,
         shares: u128,
         azero: u128,
+        fee: u128,
         virtual_shares: u128,
     }
     #[ink(event)]
     pub struct UnlockRedeemed {
         #[ink(topic)]
         staker: AccountId,
+        #[ink(topic)]
+        receiver: AccountId,
         azero: u128,
         unlock_id: u64,
+        ticket_id: u128,
+    }
+    #[ink(event)]
+    pub struct UnlockCancelled {
+        #[ink(topic)]
+        staker: AccountId,
+        unlock_id: u64,
+        batch_id: u64,
+        shares: u128,
+        azero: u128,
+    }
+    #[ink(event)]
+    pub struct ReferralRewardsClaimed {
+        #[ink(topic)]
+        referrer: AccountId,
+        shares: u128,
     }
     #[ink(event)]
-    pub struct FeesWithdrawn {
+    pub struct FeesDistributed {
+        #[ink(topic)]
+        recipient: AccountId,
         shares: u128,
     }
     #[ink(event)]
+    pub struct FeeRecipientsUpdated {
+        recipients: Vec<(AccountId, u16)>,
+    }
+    #[ink(event)]
+    pub struct FeeProposed {
+        new_fee: u16,
+        execute_time: Timestamp,
+    }
+    #[ink(event)]
+    pub struct FeeProposalCancelled {
+        new_fee: u16,
+    }
+    #[ink(event)]
+    pub struct FeeDelayProposed {
+        new_delay: u64,
+        execute_time: Timestamp,
+    }
+    #[ink(event)]
+    pub struct FeeDelayAdjusted {
+        new_delay: u64,
+    }
+    #[ink(event)]
     pub struct FeesAdjusted {
         new_fee: u16,
         virtual_shares: u128,
     }
     #[ink(event)]
-    pub struct RoleAdjustFeeTransferred {
-        new_account: AccountId,
+    pub struct FeeModeProposed {
+        new_mode: FeeMode,
+        execute_time: Timestamp,
+    }
+    #[ink(event)]
+    pub struct FeeModeProposalCancelled {
+        new_mode: FeeMode,
+    }
+    #[ink(event)]
+    pub struct FeeModeAdjusted {
+        new_mode: FeeMode,
+        virtual_shares: u128,
+    }
+    #[ink(event)]
+    pub struct ReferralFeeCutAdjusted {
+        new_cut: u16,
+    }
+    #[ink(event)]
+    pub struct BufferTargetAdjusted {
+        new_target: u16,
+    }
+    #[ink(event)]
+    pub struct InstantUnlockFeeAdjusted {
+        new_fee: u16,
+    }
+    #[ink(event)]
+    pub struct CompoundBountyProposed {
+        new_bounty: u16,
+        new_max_bounty: Balance,
+        new_interval: u64,
+        execute_time: Timestamp,
+    }
+    #[ink(event)]
+    pub struct CompoundBountyAdjusted {
+        new_bounty: u16,
+        new_max_bounty: Balance,
+        new_interval: u64,
+    }
+    #[ink(event)]
+    pub struct Paused {
+        pause_type: PauseType,
+    }
+    #[ink(event)]
+    pub struct Unpaused {
+        pause_type: PauseType,
+    }
+    #[ink(event)]
+    pub struct CooldownAdjusted {
+        era_duration: u64,
+        bonding_eras: u64,
+    }
+    #[ink(event)]
+    pub struct RoleGrantProposed {
+        role_type: RoleType,
+        account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleGrantCancelled {
+        role_type: RoleType,
+        account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleGranted {
+        role_type: RoleType,
+        account: AccountId,
+    }
+    #[ink(event)]
+    pub struct RoleRevoked {
+        role_type: RoleType,
+        account: AccountId,
+    }
+    #[ink(event)]
+    pub struct MinimumStakeAdjusted {
+        new_minimum: Balance,
+    }
+    #[ink(event)]
+    pub struct MinimumUnlockAdjusted {
+        new_minimum: u128,
+    }
+    #[ink(event)]
+    pub struct MaxTotalPooledAdjusted {
+        new_max: Balance,
+    }
+    #[ink(event)]
+    pub struct MaxAccountSharesAdjusted {
+        new_max: Option<u128>,
+    }
+    #[ink(event)]
+    pub struct CodeAnnounced {
+        code_hash: [u8; 32],
+        execute_time: Timestamp,
+    }
+    #[ink(event)]
+    pub struct CodeAnnouncementCancelled {
+        code_hash: [u8; 32],
+    }
+    #[ink(event)]
+    pub struct CodeDelayProposed {
+        new_delay: u64,
+        execute_time: Timestamp,
     }
     #[ink(event)]
-    pub struct RoleFeeToTransferred {
-        new_account: AccountId,
+    pub struct CodeDelayAdjusted {
+        new_delay: u64,
     }
     #[ink(event)]
     pub struct NewHash {
@@ -102,6 +295,7 @@ mod vault {
         #[ink(constructor)]
         pub fn new(
             share_token_hash: Hash,
+            unlock_ticket_hash: Hash,
             registry_code_hash: Hash,
             nomination_agent_hash: Hash,
             era: u64,
@@ -109,7 +303,7 @@ mod vault {
             let caller = Self::env().caller();
             let now = Self::env().block_timestamp();
 
-            let registry_ref = RegistryRef::new(caller, caller, caller, caller, nomination_agent_hash)
+            let registry_ref = RegistryRef::new(caller, nomination_agent_hash)
                 .endowment(0)
                 .code_hash(registry_code_hash)
                 .salt_bytes(now.to_le_bytes())
@@ -119,11 +313,17 @@ mod vault {
                 .code_hash(share_token_hash)
                 .salt_bytes(now.to_le_bytes())
                 .instantiate();
+            let unlock_ticket_ref = TicketRef::new()
+                .endowment(0)
+                .code_hash(unlock_ticket_hash)
+                .salt_bytes(now.to_le_bytes())
+                .instantiate();
 
             Self {
                 data: VaultData::new(
                     caller,
                     TokenRef::to_account_id(&share_token_ref),
+                    TicketRef::to_account_id(&unlock_ticket_ref),
                     registry_ref,
                     now,
                     era,
@@ -151,6 +351,27 @@ mod vault {
             Ok(())
         }
 
+        fn shares_of(&self, account: &AccountId) -> u128 {
+            let token: contract_ref!(PSP22) = self.data.shares_contract.into();
+            token.balance_of(*account)
+        }
+
+        /// Caps the staker's referred shares at the sA0 it currently holds
+        fn sync_referred_shares(&mut self, staker: AccountId, now: Timestamp) {
+            if self.data.referred_stakes.contains(staker) {
+                let balance = self.shares_of(&staker);
+                self.data.cap_referred_shares(staker, balance, now);
+            }
+        }
+
+        fn transfer_shares(&self, to: &AccountId, amount: u128) -> Result<(), VaultError> {
+            let mut token: contract_ref!(PSP22) = self.data.shares_contract.into();
+            if let Err(e) = token.transfer(*to, amount, Vec::new()) {
+                return Err(VaultError::TokenError(e));
+            }
+            Ok(())
+        }
+
         fn mint_shares(&mut self, amount: u128, to: AccountId) -> Result<(), VaultError> {
             let mut token: contract_ref!(ShareToken) = self.data.shares_contract.into();
             self.data.total_shares_minted += amount;
@@ -160,6 +381,16 @@ mod vault {
             Ok(())
         }
 
+        /// Mints the compound bounty owed to the caller as sA0
+        fn pay_compound_bounty(&mut self, caller: AccountId, compounded: Balance) -> Result<Balance, VaultError> {
+            let now = Self::env().block_timestamp();
+            let (bounty, bounty_shares) = self.data.charge_compound_bounty(compounded, now);
+            if bounty_shares > 0 {
+                self.mint_shares(bounty_shares, caller)?;
+            }
+            Ok(bounty)
+        }
+
         fn burn_shares(&mut self, amount: u128) -> Result<(), VaultError> {
             let mut token: contract_ref!(PSP22Burnable) = self.data.shares_contract.into();
             self.data.total_shares_minted -= amount;
@@ -168,6 +399,43 @@ mod vault {
             }
             Ok(())
         }
+
+        fn mint_ticket(&mut self, to: AccountId) -> Result<u128, VaultError> {
+            let mut ticket: contract_ref!(UnlockTicket) = self.data.ticket_contract.into();
+            let ticket_id = self.data.next_ticket_id;
+            self.data.next_ticket_id += 1;
+            if let Err(e) = ticket.mint(to, Id::U128(ticket_id)) {
+                return Err(VaultError::TicketError(e));
+            }
+            Ok(ticket_id)
+        }
+
+        fn burn_ticket(&mut self, ticket_id: u128) -> Result<(), VaultError> {
+            let mut ticket: contract_ref!(UnlockTicket) = self.data.ticket_contract.into();
+            if let Err(e) = ticket.burn(Id::U128(ticket_id)) {
+                return Err(VaultError::TicketError(e));
+            }
+            Ok(())
+        }
+
+        fn ticket_owner(&self, ticket_id: u128) -> Option<AccountId> {
+            let ticket: contract_ref!(PSP34) = self.data.ticket_contract.into();
+            ticket.owner_of(Id::U128(ticket_id))
+        }
+
+        fn ensure_role(&self, role_type: RoleType) -> Result<(), VaultError> {
+            if !self.data.has_role(role_type, Self::env().caller()) {
+                return Err(VaultError::InvalidPermissions);
+            }
+            Ok(())
+        }
+
+        fn ensure_not_paused(&self, pause_type: PauseType) -> Result<(), VaultError> {
+            if self.data.paused.contains(pause_type) {
+                return Err(VaultError::Paused);
+            }
+            Ok(())
+        }
     }
 
     impl RateProvider for Vault {
@@ -188,26 +456,56 @@ mod vault {
         /// AZERO must be transferred via transferred_value
         #[ink(message, payable)]
         fn stake(&mut self) -> Result<Balance, VaultError> {
+            self.stake_for(Self::env().caller())
+        }
+
+        #[ink(message, payable)]
+        fn stake_with_referral(&mut self, referral_id: AccountId) -> Result<Balance, VaultError> {
+            self.stake_for_with_referral(Self::env().caller(), referral_id)
+        }
+
+        /// Allow users to convert AZERO into sA0 on behalf of another account
+        /// Mints the beneficiary sA0 based on the redemption ratio
+        ///
+        /// Minimum AZERO amount (`minimum_stake`) is required to stake
+        /// AZERO must be transferred via transferred_value
+        #[ink(message, payable)]
+        fn stake_for(&mut self, beneficiary: AccountId) -> Result<Balance, VaultError> {
             let caller = Self::env().caller();
             let azero = Self::env().transferred_value();
 
+            self.ensure_not_paused(PauseType::Stake)?;
+
             // Verify minimum AZERO is being staked
-            if azero < 1_000_000 {
+            if azero < self.data.minimum_stake {
                 return Err(VaultError::MinimumStake);
             }
 
+            // Verify deposit limits
+            if self.data.total_pooled + azero > self.data.max_total_pooled {
+                return Err(VaultError::MaxTotalPooled);
+            }
+
             // Update fees before calculating redemption ratio and minting shares
             self.data.update_fees(Self::env().block_timestamp());
 
             let new_shares = self.get_shares_from_azero(azero);
-            self.mint_shares(new_shares, caller)?;
+
+            if let Some(max_account_shares) = self.data.max_account_shares {
+                if self.shares_of(&beneficiary) + new_shares > max_account_shares {
+                    return Err(VaultError::MaxAccountShares);
+                }
+            }
+
+            self.mint_shares(new_shares, beneficiary)?;
 
             self.data.delegate_bonding(azero)?;
 
             Self::emit_event(
                 Self::env(),
                 Event::Staked(Staked {
-                    staker: caller,
+                    payer: caller,
+                    beneficiary,
                     azero,
                     new_shares,
                     virtual_shares: self.data.total_shares_virtual, // updated in update_fees()
@@ -217,14 +515,33 @@ mod vault {
             Ok(new_shares)
         }
 
+        /// Stake on behalf of `beneficiary` crediting the referrer
+        ///
+        /// Records the minted sA0 towards the referrer's share of referral rewards
+        /// Credit is withdrawn as the beneficiary unlocks the sA0
+        /// Only the beneficiary can move its referred sA0 to a different referrer
+        /// Fails with `InvalidReferral` when the beneficiary refers itself
         #[ink(message, payable)]
-        fn stake_with_referral(&mut self, referral_id: AccountId) -> Result<Balance, VaultError> {
-            let new_shares = self.stake()?;
+        fn stake_for_with_referral(&mut self, beneficiary: AccountId, referral_id: AccountId) -> Result<Balance, VaultError> {
+            if referral_id == beneficiary {
+                return Err(VaultError::InvalidReferral);
+            }
+            if let Some(stake) = self.data.referred_stakes.get(beneficiary) {
+                if stake.referrer != referral_id && Self::env().caller() != beneficiary {
+                    return Err(VaultError::InvalidReferral);
+                }
+            }
+
+            let new_shares = self.stake_for(beneficiary)?; // updates fees
+
+            self.data.add_referred_shares(referral_id, beneficiary, new_shares);
+            self.sync_referred_shares(beneficiary, Self::env().block_timestamp());
+
             Self::emit_event(
                 Self::env(),
                 Event::Referral(Referral {
                     referral_id,
-                    staker: Self::env().caller(),
+                    staker: beneficiary,
                     azero: Self::env().transferred_value(),
                 }),
             );
@@ -234,189 +551,1265 @@ mod vault {
         /// Allow user to begin the unlock process converting shares into AZERO
         ///
         /// Transfers `shares` to the vault contract
-        /// Calculates AZERO value of shares
-        /// Creates `UnlockRequest` for the user
-        /// Delegates unbonding of the associated AZERO
-        /// Burns the associated shares tokens
+        /// Creates `UnlockRequest` for the user within the current batch
+        /// Mints a transferable PSP34 ticket to the user representing the request
+        /// Shares remain in circulation until the batch is processed via `process_batch`
         #[ink(message)]
         fn request_unlock(&mut self, shares: u128) -> Result<(), VaultError> {
             let caller = Self::env().caller();
             let now = Self::env().block_timestamp();
+            let batch_id = self.data.current_batch_id; // shadow
 
-            self.transfer_shares_from(&caller, &Self::env().account_id(), shares)?;
+            self.ensure_not_paused(PauseType::Unlock)?;
 
-            // Update fees before calculating redemption ratio and burning shares
-            self.data.update_fees(now);
+            // Verify minimum sA0 is being unlocked
+            if shares < self.data.minimum_unlock {
+                return Err(VaultError::MinimumUnlock);
+            }
 
-            let azero = self.get_azero_from_shares(shares);
+            self.transfer_shares_from(&caller, &Self::env().account_id(), shares)?;
+            self.data.remove_referred_shares(caller, shares, now);
+            self.sync_referred_shares(caller, now);
 
-            // Update user's unlock requests
-            let mut user_unlock_requests = self.data.user_unlock_requests.get(caller).unwrap_or_default();
-            user_unlock_requests.push(UnlockRequest {
-                creation_time: now,
-                azero,
-            });
-            self.data.user_unlock_requests.insert(caller, &user_unlock_requests);
+            let ticket_id = self.mint_ticket(caller)?;
 
-            // Allocate unlock quantity across nomination pools
-            self.data.delegate_unbonding(azero)?;
+            // Update user's unlock requests
+            let unlock_id = self.data.user_unlock_request_count.get(caller).unwrap_or(0);
+            self.data.user_unlock_requests.insert(
+                (caller, unlock_id),
+                &UnlockRequest {
+                    creation_time: now,
+                    batch_id,
+                    shares,
+                    ticket_id,
+                    status: UnlockStatus::Pending,
+                },
+            );
+            self.data.user_unlock_request_count.insert(caller, &(unlock_id + 1));
 
-            self.burn_shares(shares)?;
+            // Update batch
+            let mut batch = self.data.batch_unlock_requests.get(batch_id).unwrap_or_default();
+            batch.total_shares += shares;
+            self.data.batch_unlock_requests.insert(batch_id, &batch);
 
             Self::emit_event(
                 Self::env(),
                 Event::UnlockRequested(UnlockRequested {
                     staker: caller,
-                    unlock_id: (user_unlock_requests.len()-1) as u128,
+                    unlock_id,
+                    batch_id,
+                    ticket_id,
                     shares,
-                    azero,
-                    virtual_shares: self.data.total_shares_virtual, // updated in update_fees()
                 }),
             );
 
             Ok(())
         }
 
-        /// Attempts to claim unbonded AZERO from all validators
-        #[ink(message)]
-        fn delegate_withdraw_unbonded(&mut self) -> Result<(), VaultError> {
-            self.data.delegate_withdraw_unbonded()?;
-
-            Ok(())
-        }
-
-        /// Allows a user to withdraw staked AZERO
+        /// Begin unbonding of all unlock requests in the current batch
         ///
-        /// Returns original deposit amount plus interest to depositor address
-        /// Queries the redeemable amount by user AccountId and Claim Vector index
-        /// Associated unlock request must have been completed
-        /// Deletes the user's unlock request
+        /// Can be called by anyone once `batch_interval` has passed since the previous batch
+        /// Calculates AZERO value of the batched shares
+        /// Delegates unbonding of the associated AZERO in a single pass
+        /// Burns the associated shares tokens
+        /// Opens the next batch
         #[ink(message)]
-        fn redeem(&mut self, user: AccountId, unlock_id: u64) -> Result<(), VaultError> {
+        fn process_batch(&mut self) -> Result<(), VaultError> {
             let now = Self::env().block_timestamp();
+            let batch_id = self.data.current_batch_id; // shadow
 
-            let mut user_unlock_requests = self.data.user_unlock_requests.get(user).unwrap_or_default();
+            self.ensure_not_paused(PauseType::Unlock)?;
 
-            // Ensure user specified a valid unlock request index
-            if unlock_id >= user_unlock_requests.len() as u64 {
-                return Err(VaultError::InvalidUserUnlockRequest);
+            if now < self.data.last_batch_process_time + self.data.batch_interval {
+                return Err(VaultError::InvalidBatchUnlockRequest);
             }
 
-            let creation_time = user_unlock_requests[unlock_id as usize].creation_time;
-            let azero = user_unlock_requests[unlock_id as usize].azero;
-
-            // Ensure unbond has completed
-            if now < creation_time + self.data.cooldown_period {
-                return Err(VaultError::CooldownPeriod);
+            let mut batch = self.data.batch_unlock_requests.get(batch_id).unwrap_or_default();
+            if batch.total_shares == 0 {
+                return Err(VaultError::InvalidBatchUnlockRequest);
             }
 
-            // Delete completed user unlock request
-            user_unlock_requests.remove(unlock_id as usize);
-            self.data.user_unlock_requests.insert(user, &user_unlock_requests);
+            // Update fees before calculating redemption ratio and burning shares
+            self.data.update_fees(now);
+
+            let azero = self.get_azero_from_shares(batch.total_shares);
+
+            // Allocate unlock quantity across nomination pools
+            let agent_unbonds = self.data.delegate_unbonding(azero)?;
+
+            self.burn_shares(batch.total_shares)?;
 
-            // Send AZERO to user
-            Self::env().transfer(user, azero)?;
+            batch.total_azero = azero;
+            batch.process_time = Some(now);
+            batch.agent_unbonds = agent_unbonds;
+            self.data.batch_unlock_requests.insert(batch_id, &batch);
+
+            self.data.current_batch_id = batch_id + 1;
+            self.data.last_batch_process_time = now;
 
             Self::emit_event(
                 Self::env(),
-                Event::UnlockRedeemed(UnlockRedeemed {
-                    staker: user,
+                Event::BatchUnlockProcessed(BatchUnlockProcessed {
+                    batch_id,
+                    shares: batch.total_shares,
                     azero,
-                    unlock_id,
+                    virtual_shares: self.data.total_shares_virtual, // updated in update_fees()
                 }),
             );
 
             Ok(())
         }
 
-        /// Alternative method for a user to withdraw staked AZERO
+        /// Allow user to cancel a pending unlock request
         ///
-        /// This should be called instead of `redeem()` when insufficient AZERO exists in the Vault and
-        /// validator(s) have unbonded AZERO which can be claimed
+        /// Caller must have created the request and still hold its ticket
+        /// If the batch has not been processed, the original shares are returned
+        /// If the batch is unbonding, the request's AZERO is rebonded into the agents
+        /// and shares are minted at the current rate
+        /// Burns the request's ticket
+        /// Returns the amount of shares received
         #[ink(message)]
-        fn redeem_with_withdraw(&mut self, user: AccountId, unlock_id: u64) -> Result<(), VaultError> {
-            // Claim all unbonded AZERO into Vault
-            self.data.delegate_withdraw_unbonded()?;
+        fn cancel_unlock(&mut self, unlock_id: u64) -> Result<u128, VaultError> {
+            let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
 
-            self.redeem(user, unlock_id)?;
+            self.ensure_not_paused(PauseType::Unlock)?;
 
-            Ok(())
-        }
+            // Ensure caller specified a valid pending unlock request
+            let mut unlock_request = match self.data.user_unlock_requests.get((caller, unlock_id)) {
+                Some(r) if r.status == UnlockStatus::Pending => r,
+                _ => return Err(VaultError::InvalidUserUnlockRequest),
+            };
+            let batch_id = unlock_request.batch_id;
 
-        /// Compound earned interest for all validators
-        ///
-        /// Can be called by anyone
-        #[ink(message)]
-        fn compound(&mut self) -> Result<Balance, VaultError> {
-            let caller = Self::env().caller();
+            // Ensure the pending withdrawal has not been sold
+            if self.ticket_owner(unlock_request.ticket_id) != Some(caller) {
+                return Err(VaultError::InvalidPermissions);
+            }
 
-            // Delegate compounding to all agents
-            let compounded = self.data.delegate_compound()?;
+            let mut batch = self.data.batch_unlock_requests.get(batch_id).unwrap_or_default();
+
+            let (shares, azero) = match batch.process_time {
+                None => {
+                    // Shares are still held by the Vault
+                    batch.total_shares -= unlock_request.shares;
+                    self.transfer_shares(&caller, unlock_request.shares)?;
+                    (unlock_request.shares, 0)
+                },
+                Some(process_time) => {
+                    // Rebonding is only possible while unbonding
+                    if now >= process_time + self.data.cooldown_period() {
+                        return Err(VaultError::UnbondingComplete);
+                    }
+
+                    // Update fees before calculating the mint ratio
+                    self.data.update_fees(now);
+
+                    let total_pooled = self.data.total_pooled; // shadow
+                    let total_shares = self.get_total_shares(); // shadow
+
+                    let azero = self.data.delegate_rebonding(&mut batch, unlock_request.shares)?;
+
+                    // Mint at the rate prior to rebonding
+                    let shares = if total_pooled == 0 || total_shares == 0 {
+                        azero
+                    } else {
+                        self.data.pro_rata(azero, total_shares, total_pooled)
+                    };
+                    self.mint_shares(shares, caller)?;
+                    (shares, azero)
+                },
+            };
+            self.data.batch_unlock_requests.insert(batch_id, &batch);
+
+            unlock_request.status = UnlockStatus::Cancelled;
+            self.data.user_unlock_requests.insert((caller, unlock_id), &unlock_request);
+            self.burn_ticket(unlock_request.ticket_id)?;
 
             Self::emit_event(
                 Self::env(),
-                Event::Compounded(Compounded {
-                    caller,
-                    azero: compounded,
-                    virtual_shares: self.get_current_virtual_shares(),
+                Event::UnlockCancelled(UnlockCancelled {
+                    staker: caller,
+                    unlock_id,
+                    batch_id,
+                    shares,
+                    azero,
                 }),
             );
 
-            Ok(compounded)
+            Ok(shares)
         }
 
-        /// Claim fees by inflating sA0 supply
+        /// Allow user to immediately convert shares into AZERO from the idle buffer
+        ///
+        /// Transfers `shares` to the vault contract
+        /// Calculates AZERO value of shares
+        /// Deducts the instant unlock fee which remains in the pool
+        /// Burns the associated shares tokens
+        /// Sends the remaining AZERO to the caller
         ///
-        /// Caller must have the fee to role (`role_fee_to`)
-        /// Mints virtual shares as sA0 to the caller
-        /// Effectively serves as a compounding for protocol fee
-        /// sets total_shares_virtual to 0
+        /// Fails when the buffer cannot cover the payout or the payout is below `min_azero`
         #[ink(message)]
-        fn withdraw_fees(&mut self) -> Result<(), VaultError> {
+        fn instant_unlock(&mut self, shares: u128, min_azero: Balance) -> Result<Balance, VaultError> {
             let caller = Self::env().caller();
             let now = Self::env().block_timestamp();
-            let role_fee_to = self.data.role_fee_to; // shadow
 
-            if caller != role_fee_to {
-                return Err(VaultError::InvalidPermissions);
-            }
+            self.ensure_not_paused(PauseType::Unlock)?;
 
+            self.transfer_shares_from(&caller, &Self::env().account_id(), shares)?;
+
+            // Update fees before calculating redemption ratio and burning shares
             self.data.update_fees(now);
 
-            let shares = self.data.total_shares_virtual;
-            self.mint_shares(shares, role_fee_to)?;
-            self.data.total_shares_virtual = 0;
+            let azero = self.get_azero_from_shares(shares);
+            let fee = self.data.pro_rata(azero, self.data.instant_unlock_fee as u128, BIPS as u128);
+            let payout = azero - fee;
+
+            if payout < min_azero {
+                return Err(VaultError::Slippage);
+            }
+            if payout > self.data.buffer_reserve {
+                return Err(VaultError::InsufficientBuffer);
+            }
+
+            // Fee is not removed from the pool thereby accruing to all sA0 holders
+            self.data.buffer_reserve -= payout;
+            self.data.total_pooled -= payout;
+
+            self.data.remove_referred_shares(caller, shares, now);
+            self.sync_referred_shares(caller, now);
+
+            self.burn_shares(shares)?;
+
+            Self::env().transfer(caller, payout)?;
 
             Self::emit_event(
                 Self::env(),
-                Event::FeesWithdrawn(FeesWithdrawn {
+                Event::InstantUnlocked(InstantUnlocked {
+                    staker: caller,
                     shares,
+                    azero: payout,
+                    fee,
+                    virtual_shares: self.data.total_shares_virtual, // updated in update_fees()
                 }),
             );
 
-            Ok(())
+            Ok(payout)
         }
 
-        /// Upgrade the contract by the ink env set_code_hash function
+        /// Attempts to claim unbonded AZERO from all validators
+        #[ink(message)]
+        fn delegate_withdraw_unbonded(&mut self) -> Result<(), VaultError> {
+            self.data.delegate_withdraw_unbonded(Self::env().block_timestamp())?;
+
+            Ok(())
+        }
+
+        /// Attempts to claim unbonded AZERO from up to `count` validators beginning at index `start`
+        ///
+        /// Can be called by anyone
+        /// Returns the index of the next agent to withdraw from, zero once all agents are covered
+        #[ink(message)]
+        fn withdraw_unbonded_range(&mut self, start: u64, count: u64) -> Result<u64, VaultError> {
+            self.data
+                .delegate_withdraw_unbonded_range(start, count, Self::env().block_timestamp())
+        }
+
+        /// Allows a user to withdraw staked AZERO
+        ///
+        /// Returns original deposit amount plus interest to the holder of the unlock ticket
+        /// Queries the redeemable amount by user AccountId and unlock id
+        /// Redeemable amount is the request's pro rata share of its batch
+        /// Associated batch must have been processed and completed unbonding
+        /// Marks the user's unlock request as redeemed and burns its ticket
+        /// Returns the amount of AZERO redeemed
+        #[ink(message)]
+        fn redeem(&mut self, user: AccountId, unlock_id: u64) -> Result<Balance, VaultError> {
+            let now = Self::env().block_timestamp();
+
+            // Ensure user specified a valid pending unlock request
+            let mut unlock_request = match self.data.user_unlock_requests.get((user, unlock_id)) {
+                Some(r) if r.status == UnlockStatus::Pending => r,
+                _ => return Err(VaultError::InvalidUserUnlockRequest),
+            };
+            let shares = unlock_request.shares;
+            let ticket_id = unlock_request.ticket_id;
+
+            let batch = self.data.batch_unlock_requests.get(unlock_request.batch_id).unwrap_or_default();
+
+            // Ensure batch has been processed
+            let process_time = match batch.process_time {
+                Some(t) => t,
+                None => return Err(VaultError::InvalidBatchUnlockRequest),
+            };
+
+            // Ensure unbond has completed
+            if now < process_time + self.data.cooldown_period() {
+                return Err(VaultError::CooldownPeriod);
+            }
+
+            let azero = self.data.pro_rata(batch.total_azero, shares, batch.total_shares);
+
+            // Ensure neither the buffer nor withdrawn rebalance funds are used to fund redemptions
+            if Self::env().balance() < self.data.buffer_reserve + self.data.rebalance_withdrawn + azero {
+                return Err(VaultError::InsufficientBuffer);
+            }
+
+            // Whoever holds the ticket is entitled to the AZERO
+            let receiver = self.ticket_owner(ticket_id).ok_or(VaultError::InvalidUserUnlockRequest)?;
+
+            // Mark user unlock request as completed
+            unlock_request.status = UnlockStatus::Redeemed;
+            self.data.user_unlock_requests.insert((user, unlock_id), &unlock_request);
+            self.burn_ticket(ticket_id)?;
+
+            // Send AZERO to ticket holder
+            Self::env().transfer(receiver, azero)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::UnlockRedeemed(UnlockRedeemed {
+                    staker: user,
+                    receiver,
+                    azero,
+                    unlock_id,
+                    ticket_id,
+                }),
+            );
+
+            Ok(azero)
+        }
+
+        /// Alternative method for a user to withdraw staked AZERO
+        ///
+        /// This should be called instead of `redeem()` when insufficient AZERO exists in the Vault and
+        /// validator(s) have unbonded AZERO which can be claimed
+        #[ink(message)]
+        fn redeem_with_withdraw(&mut self, user: AccountId, unlock_id: u64) -> Result<Balance, VaultError> {
+            // Claim all unbonded AZERO into Vault
+            self.data.delegate_withdraw_unbonded(Self::env().block_timestamp())?;
+
+            self.redeem(user, unlock_id)
+        }
+
+        /// Compound earned interest for all validators
+        ///
+        /// Can be called by anyone
+        #[ink(message)]
+        fn compound(&mut self) -> Result<Balance, VaultError> {
+            let caller = Self::env().caller();
+
+            self.ensure_not_paused(PauseType::Compound)?;
+
+            // Update fees before charging the performance fee
+            self.data.update_fees(Self::env().block_timestamp());
+
+            // Delegate compounding to all agents
+            let compounded = self.data.delegate_compound()?;
+
+            self.data.charge_performance_fee(compounded);
+            let bounty = self.pay_compound_bounty(caller, compounded)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::Compounded(Compounded {
+                    caller,
+                    azero: compounded,
+                    virtual_shares: self.get_current_virtual_shares(),
+                    bounty,
+                }),
+            );
+
+            Ok(compounded)
+        }
+
+        /// Claim staking rewards of the given eras for the validators of up to `count` agents
+        /// beginning at index `start` then compound the same agents
+        ///
+        /// Can be called by anyone
+        /// Eras already claimed are skipped, at most `MAX_PAYOUT_ERAS` eras per call
+        /// Advances the compound cursor like `compound_range`
+        #[ink(message)]
+        fn payout_and_compound(&mut self, eras: Vec<u32>, start: u64, count: u64) -> Result<Balance, VaultError> {
+            if eras.is_empty() || eras.len() > MAX_PAYOUT_ERAS {
+                return Err(VaultError::MaxPayoutEras);
+            }
+
+            self.ensure_not_paused(PauseType::Compound)?;
+
+            self.data.delegate_payout(&eras, start, count)?;
+
+            self.compound_range(start, count)
+        }
+
+        /// Compound earned interest for up to `count` validators beginning at index `start`
+        ///
+        /// Can be called by anyone
+        /// Spreads compounding of many agents across transactions, see `get_compound_cursor`
+        #[ink(message)]
+        fn compound_range(&mut self, start: u64, count: u64) -> Result<Balance, VaultError> {
+            let caller = Self::env().caller();
+
+            self.ensure_not_paused(PauseType::Compound)?;
+
+            // Update fees before charging the performance fee
+            self.data.update_fees(Self::env().block_timestamp());
+
+            let (compounded, _cursor) = self.data.delegate_compound_range(start, count)?;
+
+            self.data.charge_performance_fee(compounded);
+            let bounty = self.pay_compound_bounty(caller, compounded)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::Compounded(Compounded {
+                    caller,
+                    azero: compounded,
+                    virtual_shares: self.get_current_virtual_shares(),
+                    bounty,
+                }),
+            );
+
+            Ok(compounded)
+        }
+
+        /// Report a validator slash of an agent
+        ///
+        /// Caller must have the slash reporter role (`RoleType::SlashReporter`)
+        /// `staked` is the protocol stake remaining in the agent's staking ledger, excluding the initial bond
+        /// `unbonding` is the protocol AZERO remaining in the agent's unlocking chunks
+        /// The staked loss is socialized across all sA0 holders by lowering `total_pooled`
+        /// The unbonding loss is charged pro rata to the batches still unbonding from the agent
+        #[ink(message)]
+        fn report_slash(
+            &mut self,
+            agent: AccountId,
+            staked: Balance,
+            unbonding: Balance,
+        ) -> Result<(Balance, Balance), VaultError> {
+            self.ensure_role(RoleType::SlashReporter)?;
+
+            let now = Self::env().block_timestamp();
+            let (staked_slashed, unbonding_slashed) = self.data.delegate_slash(agent, staked, unbonding)?;
+
+            let haircuts = if unbonding_slashed > 0 {
+                self.data.haircut_batches(agent, unbonding_slashed, now)
+            } else {
+                Vec::new()
+            };
+            for (batch_id, azero) in haircuts.into_iter() {
+                Self::emit_event(
+                    Self::env(),
+                    Event::BatchSlashed(BatchSlashed {
+                        batch_id,
+                        agent,
+                        azero,
+                    }),
+                );
+            }
+
+            Self::emit_event(
+                Self::env(),
+                Event::Slashed(Slashed {
+                    agent,
+                    azero: staked_slashed,
+                    unbonding_azero: unbonding_slashed,
+                }),
+            );
+
+            Ok((staked_slashed, unbonding_slashed))
+        }
+
+        /// Begin moving stake from over-allocated agents to under-allocated agents
+        ///
+        /// Caller must have the rebalancer role (`RoleType::Rebalancer`)
+        /// Unbonds up to `max_amount` AZERO from over-allocated agents, weighted by their imbalance
+        /// Only a single rebalance can be in flight, see `complete_rebalance`
+        #[ink(message)]
+        fn rebalance(&mut self, max_amount: Balance) -> Result<Balance, VaultError> {
+            let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
+
+            self.ensure_role(RoleType::Rebalancer)?;
+
+            let azero = self.data.delegate_rebalance(max_amount, now)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::RebalanceStarted(RebalanceStarted {
+                    caller,
+                    azero,
+                }),
+            );
+
+            Ok(azero)
+        }
+
+        /// Complete the in-flight rebalance once unbonding has completed
+        ///
+        /// Can be called by anyone
+        /// Bonds the recovered AZERO to under-allocated agents
+        #[ink(message)]
+        fn complete_rebalance(&mut self) -> Result<Balance, VaultError> {
+            let caller = Self::env().caller();
+            let now = Self::env().block_timestamp();
+
+            let azero = self.data.delegate_complete_rebalance(now)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::RebalanceCompleted(RebalanceCompleted {
+                    caller,
+                    azero,
+                }),
+            );
+
+            Ok(azero)
+        }
+
+        /// Claim fees by inflating sA0 supply
+        ///
+        /// Caller must have the fee to role (`RoleType::FeeTo`)
+        /// Distributes accrued fees to the fee recipients via `distribute_fees`
+        #[ink(message)]
+        fn withdraw_fees(&mut self) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::FeeTo)?;
+
+            self.distribute_fees()
+        }
+
+        /// Claim fees on behalf of all fee recipients by inflating sA0 supply
+        ///
+        /// Can be called by anyone
+        /// Mints virtual shares as sA0 to each recipient (`fee_recipients`) according to their weight
+        /// Rounding dust remains in total_shares_virtual for the next distribution
+        #[ink(message)]
+        fn distribute_fees(&mut self) -> Result<(), VaultError> {
+            let now = Self::env().block_timestamp();
+
+            self.data.update_fees(now);
+
+            let total_shares = self.data.total_shares_virtual; // shadow
+            let recipients = self.data.fee_recipients.clone();
+
+            for (recipient, weight) in recipients {
+                let shares = self.data.pro_rata(total_shares, weight as u128, BIPS as u128);
+                if shares == 0 {
+                    continue;
+                }
+
+                self.mint_shares(shares, recipient)?;
+                self.data.total_shares_virtual -= shares;
+
+                Self::emit_event(
+                    Self::env(),
+                    Event::FeesDistributed(FeesDistributed {
+                        recipient,
+                        shares,
+                    }),
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Update the accounts receiving protocol fees via `distribute_fees`
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        /// Weights are expressed in basis points and must sum to `BIPS`
+        #[ink(message)]
+        fn set_fee_recipients(&mut self, recipients: Vec<(AccountId, u16)>) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustFee)?;
+            if recipients.is_empty() || recipients.len() > MAX_FEE_RECIPIENTS {
+                return Err(VaultError::InvalidFeeRecipients);
+            }
+            if self.data.fee_recipients == recipients {
+                return Err(VaultError::NoChange);
+            }
+
+            let mut total_weight: u32 = 0;
+            for (i, (recipient, weight)) in recipients.iter().enumerate() {
+                if recipients[..i].iter().any(|(r, _)| r == recipient) {
+                    return Err(VaultError::Duplication);
+                }
+                total_weight += *weight as u32;
+            }
+            if total_weight != BIPS as u32 {
+                return Err(VaultError::InvalidPercent);
+            }
+
+            self.data.fee_recipients = recipients.clone();
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeeRecipientsUpdated(FeeRecipientsUpdated {
+                    recipients,
+                }),
+            );
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn get_fee_recipients(&self) -> Vec<(AccountId, u16)> {
+            self.data.fee_recipients.clone()
+        }
+
+        /// Stops crediting the referrer for referred sA0 the staker no longer holds
+        ///
+        /// Permissionless, settles referred sA0 transferred away from the staker
+        #[ink(message)]
+        fn sync_referred_stake(&mut self, staker: AccountId) -> Result<(), VaultError> {
+            self.sync_referred_shares(staker, Self::env().block_timestamp());
+            Ok(())
+        }
+
+        /// Mints the caller's accrued referral rewards
+        #[ink(message)]
+        fn claim_referral_rewards(&mut self) -> Result<u128, VaultError> {
+            let caller = Self::env().caller();
+
+            self.data.update_fees(Self::env().block_timestamp());
+
+            let mut referrer = self.data.referrers.get(caller).unwrap_or_default();
+            self.data.settle_referrer(&mut referrer);
+
+            let shares = referrer.rewards;
+            if shares == 0 {
+                return Err(VaultError::ZeroReferralRewards);
+            }
+
+            referrer.rewards = 0;
+            self.data.referrers.insert(caller, &referrer);
+            self.data.total_shares_referral -= shares;
+            self.mint_shares(shares, caller)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::ReferralRewardsClaimed(ReferralRewardsClaimed {
+                    referrer: caller,
+                    shares,
+                }),
+            );
+
+            Ok(shares)
+        }
+
+        /// Announce an upgrade of the contract to be executed after `code_delay`
+        ///
+        /// Caller must have the set code role (`RoleType::SetCode`)
+        /// Replaces any pending announcement
+        #[ink(message)]
+        fn announce_code(&mut self, code_hash: [u8; 32]) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::SetCode)?;
+
+            let execute_time = Self::env().block_timestamp() + self.data.code_delay;
+            self.data.code_announcement = Some(CodeAnnouncement {
+                code_hash,
+                execute_time,
+            });
+
+            Self::emit_event(
+                Self::env(),
+                Event::CodeAnnounced(CodeAnnounced {
+                    code_hash,
+                    execute_time,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Upgrade the contract by the ink env set_code_hash function
+        ///
+        /// Caller must have the set code role (`RoleType::SetCode`)
+        /// `code_hash` must match the announcement, which must have been pending for at least `code_delay`
+        /// See ink documentation for details https://paritytech.github.io/ink/ink_env/fn.set_code_hash.html
+        #[ink(message)]
+        fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::SetCode)?;
+
+            let announcement = self.data.code_announcement.take().ok_or(VaultError::NoCodeAnnouncement)?;
+            if announcement.code_hash != code_hash {
+                return Err(VaultError::CodeHashMismatch);
+            }
+            if Self::env().block_timestamp() < announcement.execute_time {
+                return Err(VaultError::CodeTimelock);
+            }
+
+            ink::env::set_code_hash(&code_hash)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::NewHash(NewHash {
+                    code_hash,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Cancel the pending upgrade
+        ///
+        /// Caller must have the set code role (`RoleType::SetCode`)
+        #[ink(message)]
+        fn cancel_code(&mut self) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::SetCode)?;
+
+            let announcement = self.data.code_announcement.take().ok_or(VaultError::NoCodeAnnouncement)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::CodeAnnouncementCancelled(CodeAnnouncementCancelled {
+                    code_hash: announcement.code_hash,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Update the delay between announcing and executing an upgrade
+        ///
+        /// Caller must have the set code role (`RoleType::SetCode`)
+        /// Cannot be lower than `MIN_CODE_DELAY` nor the cooldown period plus the batch interval
+        /// Increases apply immediately and drop any pending decrease
+        /// Decreases are proposed and executed via `execute_code_delay` after the current `code_delay`
+        /// Does not affect the pending announcement
+        #[ink(message)]
+        fn adjust_code_delay(&mut self, new_delay: u64) -> Result<(), VaultError> {
+            let code_delay = self.data.code_delay; // shadow
+
+            self.ensure_role(RoleType::SetCode)?;
+            if code_delay == new_delay {
+                return Err(VaultError::NoChange);
+            }
+            if new_delay < self.data.min_code_delay() {
+                return Err(VaultError::InvalidCodeDelay);
+            }
+
+            if new_delay > code_delay {
+                self.data.code_delay = new_delay;
+                self.data.code_delay_proposal = None;
+
+                Self::emit_event(
+                    Self::env(),
+                    Event::CodeDelayAdjusted(CodeDelayAdjusted {
+                        new_delay,
+                    }),
+                );
+            } else {
+                let execute_time = Self::env().block_timestamp() + code_delay;
+                self.data.code_delay_proposal = Some(CodeDelayProposal {
+                    new_delay,
+                    execute_time,
+                });
+
+                Self::emit_event(
+                    Self::env(),
+                    Event::CodeDelayProposed(CodeDelayProposed {
+                        new_delay,
+                        execute_time,
+                    }),
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Decrease the code delay to the pending proposal
+        ///
+        /// Caller must have the set code role (`RoleType::SetCode`)
+        /// Proposal must have been pending for at least the `code_delay` in effect when it was made
+        #[ink(message)]
+        fn execute_code_delay(&mut self) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::SetCode)?;
+
+            let proposal = self.data.code_delay_proposal.take().ok_or(VaultError::NoCodeDelayProposal)?;
+            if Self::env().block_timestamp() < proposal.execute_time {
+                return Err(VaultError::CodeTimelock);
+            }
+            let new_delay = proposal.new_delay;
+            if new_delay < self.data.min_code_delay() {
+                return Err(VaultError::InvalidCodeDelay);
+            }
+
+            self.data.code_delay = new_delay;
+
+            Self::emit_event(
+                Self::env(),
+                Event::CodeDelayAdjusted(CodeDelayAdjusted {
+                    new_delay,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Permanently disable upgrades
+        ///
+        /// Caller must have the set code role (`RoleType::SetCode`)
+        /// Revokes the role from all members and makes it its own admin so it can never be granted again
+        /// Pending proposals of the role are voided by the admin change
+        #[ink(message)]
+        fn disable_set_code(&mut self) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::SetCode)?;
+
+            for member in self.data.access_control.get_role_members(RoleType::SetCode.id()) {
+                self.data.access_control.revoke(RoleType::SetCode.id(), member)?;
+            }
+            self.data
+                .access_control
+                .set_role_admin(RoleType::SetCode.id(), RoleType::SetCode.id());
+            self.data.code_announcement = None;
+            self.data.code_delay_proposal = None;
+
+            Self::emit_event(
+                Self::env(),
+                Event::SetHashDisabled(SetHashDisabled {}),
+            );
+
+            Ok(())
+        }
+
+        /// Propose a new protocol fee to be executed after `fee_delay`
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        /// Fee cannot exceed `MAX_FEE` nor change by more than `MAX_FEE_STEP`
+        /// Replaces any pending proposal
+        #[ink(message)]
+        fn propose_fee(&mut self, new_fee: u16) -> Result<(), VaultError> {
+            let fee_percentage = self.data.fee_percentage; // shadow
+
+            self.ensure_role(RoleType::AdjustFee)?;
+            if fee_percentage == new_fee {
+                return Err(VaultError::NoChange);
+            }
+            if new_fee > MAX_FEE {
+                return Err(VaultError::InvalidPercent);
+            }
+            if new_fee.abs_diff(fee_percentage) > MAX_FEE_STEP {
+                return Err(VaultError::MaxFeeStep);
+            }
+
+            let execute_time = Self::env().block_timestamp() + self.data.fee_delay;
+            self.data.fee_proposal = Some(FeeProposal {
+                new_fee,
+                execute_time,
+            });
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeeProposed(FeeProposed {
+                    new_fee,
+                    execute_time,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Update the protocol fee to the pending proposal
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        /// Proposal must have been pending for at least `fee_delay`
+        /// Updates the total_shares_virtual accumulator at the old fee level first
+        #[ink(message)]
+        fn execute_fee(&mut self) -> Result<(), VaultError> {
+            let now = Self::env().block_timestamp();
+
+            self.ensure_role(RoleType::AdjustFee)?;
+
+            let proposal = self.data.fee_proposal.take().ok_or(VaultError::NoFeeProposal)?;
+            if now < proposal.execute_time {
+                return Err(VaultError::FeeTimelock);
+            }
+            let new_fee = proposal.new_fee;
+
+            self.data.update_fees(now);
+            self.data.fee_percentage = new_fee;
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeesAdjusted(FeesAdjusted {
+                    new_fee,
+                    virtual_shares: self.data.total_shares_virtual, // updated in update_fees()
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Cancel the pending fee proposal
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        #[ink(message)]
+        fn cancel_fee(&mut self) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustFee)?;
+
+            let proposal = self.data.fee_proposal.take().ok_or(VaultError::NoFeeProposal)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeeProposalCancelled(FeeProposalCancelled {
+                    new_fee: proposal.new_fee,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Update the delay between proposing and executing a fee change
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        /// Cannot be lower than `MIN_FEE_DELAY`
+        /// Increases apply immediately and drop any pending decrease
+        /// Decreases are proposed and executed via `execute_fee_delay` after the current `fee_delay`
+        /// Does not affect the pending fee or fee mode proposal
+        #[ink(message)]
+        fn adjust_fee_delay(&mut self, new_delay: u64) -> Result<(), VaultError> {
+            let fee_delay = self.data.fee_delay; // shadow
+
+            self.ensure_role(RoleType::AdjustFee)?;
+            if fee_delay == new_delay {
+                return Err(VaultError::NoChange);
+            }
+            if new_delay < MIN_FEE_DELAY {
+                return Err(VaultError::InvalidFeeDelay);
+            }
+
+            if new_delay > fee_delay {
+                self.data.fee_delay = new_delay;
+                self.data.fee_delay_proposal = None;
+
+                Self::emit_event(
+                    Self::env(),
+                    Event::FeeDelayAdjusted(FeeDelayAdjusted {
+                        new_delay,
+                    }),
+                );
+            } else {
+                let execute_time = Self::env().block_timestamp() + fee_delay;
+                self.data.fee_delay_proposal = Some(FeeDelayProposal {
+                    new_delay,
+                    execute_time,
+                });
+
+                Self::emit_event(
+                    Self::env(),
+                    Event::FeeDelayProposed(FeeDelayProposed {
+                        new_delay,
+                        execute_time,
+                    }),
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Decrease the fee delay to the pending proposal
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        /// Proposal must have been pending for at least the `fee_delay` in effect when it was made
+        #[ink(message)]
+        fn execute_fee_delay(&mut self) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustFee)?;
+
+            let proposal = self.data.fee_delay_proposal.take().ok_or(VaultError::NoFeeDelayProposal)?;
+            if Self::env().block_timestamp() < proposal.execute_time {
+                return Err(VaultError::FeeTimelock);
+            }
+            let new_delay = proposal.new_delay;
+
+            self.data.fee_delay = new_delay;
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeeDelayAdjusted(FeeDelayAdjusted {
+                    new_delay,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Propose a new model for charging protocol fees to be executed after `fee_delay`
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        /// Replaces any pending proposal
+        #[ink(message)]
+        fn propose_fee_mode(&mut self, new_mode: FeeMode) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustFee)?;
+            if self.data.fee_mode == new_mode {
+                return Err(VaultError::NoChange);
+            }
+
+            let execute_time = Self::env().block_timestamp() + self.data.fee_delay;
+            self.data.fee_mode_proposal = Some(FeeModeProposal {
+                new_mode,
+                execute_time,
+            });
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeeModeProposed(FeeModeProposed {
+                    new_mode,
+                    execute_time,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Update the model used for charging protocol fees to the pending proposal
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        /// Proposal must have been pending for at least `fee_delay`
+        /// Fees accrued under the old mode are settled first
+        /// `fee_percentage` is kept and reinterpreted under the new mode
+        #[ink(message)]
+        fn execute_fee_mode(&mut self) -> Result<(), VaultError> {
+            let now = Self::env().block_timestamp();
+
+            self.ensure_role(RoleType::AdjustFee)?;
+
+            let proposal = self.data.fee_mode_proposal.take().ok_or(VaultError::NoFeeProposal)?;
+            if now < proposal.execute_time {
+                return Err(VaultError::FeeTimelock);
+            }
+            let new_mode = proposal.new_mode;
+
+            self.data.update_fees(now);
+            self.data.fee_mode = new_mode;
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeeModeAdjusted(FeeModeAdjusted {
+                    new_mode,
+                    virtual_shares: self.data.total_shares_virtual, // updated in update_fees()
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Cancel the pending fee mode proposal
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        #[ink(message)]
+        fn cancel_fee_mode(&mut self) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustFee)?;
+
+            let proposal = self.data.fee_mode_proposal.take().ok_or(VaultError::NoFeeProposal)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::FeeModeProposalCancelled(FeeModeProposalCancelled {
+                    new_mode: proposal.new_mode,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Update the share of accrued fees paid to referrers
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        /// Fees accrued at the old cut are distributed first
+        #[ink(message)]
+        fn adjust_referral_fee_cut(&mut self, new_cut: u16) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustFee)?;
+            if self.data.referral_fee_cut == new_cut {
+                return Err(VaultError::NoChange);
+            }
+            if new_cut > BIPS {
+                return Err(VaultError::InvalidPercent);
+            }
+
+            self.data.update_fees(Self::env().block_timestamp());
+            self.data.referral_fee_cut = new_cut;
+
+            Self::emit_event(
+                Self::env(),
+                Event::ReferralFeeCutAdjusted(ReferralFeeCutAdjusted {
+                    new_cut,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Update the share of `total_pooled` kept idle in the buffer
+        ///
+        /// Caller must have the adjust limits role (`RoleType::AdjustLimits`)
+        /// Takes effect as the buffer is topped up by new stakes
+        #[ink(message)]
+        fn adjust_buffer_target(&mut self, new_target: u16) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustLimits)?;
+            if self.data.buffer_target == new_target {
+                return Err(VaultError::NoChange);
+            }
+            if new_target > BIPS {
+                return Err(VaultError::InvalidPercent);
+            }
+
+            self.data.buffer_target = new_target;
+
+            Self::emit_event(
+                Self::env(),
+                Event::BufferTargetAdjusted(BufferTargetAdjusted {
+                    new_target,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Update the fee charged on instant unlocks
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        #[ink(message)]
+        fn adjust_instant_unlock_fee(&mut self, new_fee: u16) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustFee)?;
+            if self.data.instant_unlock_fee == new_fee {
+                return Err(VaultError::NoChange);
+            }
+            if new_fee >= BIPS {
+                return Err(VaultError::InvalidPercent);
+            }
+
+            self.data.instant_unlock_fee = new_fee;
+
+            Self::emit_event(
+                Self::env(),
+                Event::InstantUnlockFeeAdjusted(InstantUnlockFeeAdjusted {
+                    new_fee,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Update the bounty paid to callers of `compound`
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        /// `new_bounty` is the cut of compounded AZERO expressed in basis points, capped by `new_max_bounty`
+        /// `new_interval` is the minimum time between bounty payouts
+        /// Bounty cannot exceed `MAX_COMPOUND_BOUNTY` nor `MAX_COMPOUND_BOUNTY_AZERO`
+        /// Decreases apply immediately and drop any pending increase
+        /// Increases are proposed and executed via `execute_compound_bounty` after `fee_delay`
+        #[ink(message)]
+        fn adjust_compound_bounty(
+            &mut self,
+            new_bounty: u16,
+            new_max_bounty: Balance,
+            new_interval: u64,
+        ) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustFee)?;
+            if self.data.compound_bounty == new_bounty
+                && self.data.max_compound_bounty == new_max_bounty
+                && self.data.compound_bounty_interval == new_interval
+            {
+                return Err(VaultError::NoChange);
+            }
+            if new_bounty > MAX_COMPOUND_BOUNTY {
+                return Err(VaultError::InvalidPercent);
+            }
+            if new_max_bounty > MAX_COMPOUND_BOUNTY_AZERO {
+                return Err(VaultError::MaxCompoundBounty);
+            }
+
+            let increase = new_bounty > self.data.compound_bounty
+                || new_max_bounty > self.data.max_compound_bounty
+                || new_interval < self.data.compound_bounty_interval;
+            if increase {
+                let execute_time = Self::env().block_timestamp() + self.data.fee_delay;
+                self.data.compound_bounty_proposal = Some(CompoundBountyProposal {
+                    new_bounty,
+                    new_max_bounty,
+                    new_interval,
+                    execute_time,
+                });
+
+                Self::emit_event(
+                    Self::env(),
+                    Event::CompoundBountyProposed(CompoundBountyProposed {
+                        new_bounty,
+                        new_max_bounty,
+                        new_interval,
+                        execute_time,
+                    }),
+                );
+            } else {
+                self.data.compound_bounty = new_bounty;
+                self.data.max_compound_bounty = new_max_bounty;
+                self.data.compound_bounty_interval = new_interval;
+                self.data.compound_bounty_proposal = None;
+
+                Self::emit_event(
+                    Self::env(),
+                    Event::CompoundBountyAdjusted(CompoundBountyAdjusted {
+                        new_bounty,
+                        new_max_bounty,
+                        new_interval,
+                    }),
+                );
+            }
+
+            Ok(())
+        }
+
+        /// Increase the compound bounty to the pending proposal
+        ///
+        /// Caller must have the adjust fee role (`RoleType::AdjustFee`)
+        /// Proposal must have been pending for at least `fee_delay`
+        #[ink(message)]
+        fn execute_compound_bounty(&mut self) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustFee)?;
+
+            let proposal = self.data.compound_bounty_proposal.take().ok_or(VaultError::NoFeeProposal)?;
+            if Self::env().block_timestamp() < proposal.execute_time {
+                return Err(VaultError::FeeTimelock);
+            }
+
+            self.data.compound_bounty = proposal.new_bounty;
+            self.data.max_compound_bounty = proposal.new_max_bounty;
+            self.data.compound_bounty_interval = proposal.new_interval;
+
+            Self::emit_event(
+                Self::env(),
+                Event::CompoundBountyAdjusted(CompoundBountyAdjusted {
+                    new_bounty: proposal.new_bounty,
+                    new_max_bounty: proposal.new_max_bounty,
+                    new_interval: proposal.new_interval,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Update the era duration and number of bonding eras which make up the cooldown period
         ///
-        /// The set code role (`role_set_code`) must be set
-        /// Caller must have the set code role (`role_set_code`)
-        /// See ink documentation for details https://paritytech.github.io/ink/ink_env/fn.set_code_hash.html
+        /// Caller must have the adjust limits role (`RoleType::AdjustLimits`)
+        /// Should mirror the chain's era length and `staking.bondingDuration`
+        /// Applies to all pending unlock requests
         #[ink(message)]
-        fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), VaultError> {
-            let caller = Self::env().caller();
-            let role_set_code = self.data.role_set_code; // shadow
-
-            if role_set_code.is_none() || caller != role_set_code.unwrap() {
-                return Err(VaultError::InvalidPermissions);
+        fn adjust_cooldown(&mut self, era_duration: u64, bonding_eras: u64) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustLimits)?;
+            if era_duration == 0 || bonding_eras == 0 {
+                return Err(VaultError::InvalidCooldown);
+            }
+            if self.data.era_duration == era_duration && self.data.bonding_eras == bonding_eras {
+                return Err(VaultError::NoChange);
             }
 
-            ink::env::set_code_hash(&code_hash)?;
+            self.data.era_duration = era_duration;
+            self.data.bonding_eras = bonding_eras;
 
             Self::emit_event(
                 Self::env(),
-                Event::NewHash(NewHash {
-                    code_hash,
+                Event::CooldownAdjusted(CooldownAdjusted {
+                    era_duration,
+                    bonding_eras,
                 }),
             );
 
@@ -424,54 +1817,67 @@ mod vault {
         }
 
         #[ink(message)]
-        fn disable_set_code(&mut self) -> Result<(), VaultError> {
-            let caller = Self::env().caller();
-            let role_set_code = self.data.role_set_code; // shadow
+        fn get_era_duration(&self) -> u64 {
+            self.data.era_duration
+        }
+
+        #[ink(message)]
+        fn get_bonding_eras(&self) -> u64 {
+            self.data.bonding_eras
+        }
+
+        #[ink(message)]
+        fn get_cooldown_period(&self) -> u64 {
+            self.data.cooldown_period()
+        }
 
-            if role_set_code.is_none() {
+        /// Update the minimum AZERO required to stake
+        ///
+        /// Caller must have the adjust limits role (`RoleType::AdjustLimits`)
+        /// Must allow every weighted agent to receive at least `MIN_AGENT_BOND_EXTRA`
+        #[ink(message)]
+        fn adjust_minimum_stake(&mut self, new_minimum: Balance) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustLimits)?;
+            if self.data.minimum_stake == new_minimum {
                 return Err(VaultError::NoChange);
             }
-            if caller != role_set_code.unwrap() {
-                return Err(VaultError::InvalidPermissions);
+            if new_minimum < self.data.get_minimum_bond_extra() {
+                return Err(VaultError::MinimumBondExtra);
             }
 
-            self.data.role_set_code = None;
+            self.data.minimum_stake = new_minimum;
 
             Self::emit_event(
                 Self::env(),
-                Event::SetHashDisabled(SetHashDisabled {}),
+                Event::MinimumStakeAdjusted(MinimumStakeAdjusted {
+                    new_minimum,
+                }),
             );
 
             Ok(())
         }
 
-        /// Update the protocol fee
+        /// Update the minimum sA0 required to request an unlock
         ///
-        /// Caller must have the adjust fee role (`role_adjust_fee`)
-        /// Updates the total_shares_virtual accumulator at the old fee level first
+        /// Caller must have the adjust limits role (`RoleType::AdjustLimits`)
+        /// Must currently be worth enough AZERO for every weighted agent to unbond at least `MIN_AGENT_BOND_EXTRA`
+        /// Zero disables the minimum
         #[ink(message)]
-        fn adjust_fee(&mut self, new_fee: u16) -> Result<(), VaultError> {
-            let caller = Self::env().caller();
-            let now = Self::env().block_timestamp();
-
-            if caller != self.data.role_adjust_fee {
-                return Err(VaultError::InvalidPermissions);
-            }
-            if self.data.fee_percentage == new_fee {
+        fn adjust_minimum_unlock(&mut self, new_minimum: u128) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustLimits)?;
+            if self.data.minimum_unlock == new_minimum {
                 return Err(VaultError::NoChange);
             }
-            if new_fee >= BIPS {
-                return Err(VaultError::InvalidPercent);
+            if new_minimum > 0 && self.get_azero_from_shares(new_minimum) < self.data.get_minimum_bond_extra() {
+                return Err(VaultError::MinimumBondExtra);
             }
 
-            self.data.update_fees(now);
-            self.data.fee_percentage = new_fee;
+            self.data.minimum_unlock = new_minimum;
 
             Self::emit_event(
                 Self::env(),
-                Event::FeesAdjusted(FeesAdjusted {
-                    new_fee,
-                    virtual_shares: self.data.total_shares_virtual, // updated in update_fees()
+                Event::MinimumUnlockAdjusted(MinimumUnlockAdjusted {
+                    new_minimum,
                 }),
             );
 
@@ -479,31 +1885,100 @@ mod vault {
         }
 
         #[ink(message)]
-        fn get_role_adjust_fee(&self) -> AccountId {
-            self.data.role_adjust_fee
+        fn get_minimum_stake(&self) -> Balance {
+            self.data.minimum_stake
+        }
+
+        #[ink(message)]
+        fn get_minimum_unlock(&self) -> u128 {
+            self.data.minimum_unlock
         }
 
-        /// Transfers adjust fee role to a new account
+        /// Update the maximum AZERO which can be pooled via staking
         ///
-        /// Caller must have the adjust fee role (`role_adjust_fee`)
+        /// Caller must have the adjust limits role (`RoleType::AdjustLimits`)
+        /// Lowering below `total_pooled` only blocks new stakes
         #[ink(message)]
-        fn transfer_role_adjust_fee(&mut self, new_account: AccountId) -> Result<(), VaultError> {
-            let caller = Self::env().caller();
-            let role_adjust_fee = self.data.role_adjust_fee; // shadow
+        fn adjust_max_total_pooled(&mut self, new_max: Balance) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustLimits)?;
+            if self.data.max_total_pooled == new_max {
+                return Err(VaultError::NoChange);
+            }
 
-            if caller != role_adjust_fee {
-                return Err(VaultError::InvalidPermissions);
+            self.data.max_total_pooled = new_max;
+
+            Self::emit_event(
+                Self::env(),
+                Event::MaxTotalPooledAdjusted(MaxTotalPooledAdjusted {
+                    new_max,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Update the maximum sA0 a single account can hold after staking
+        ///
+        /// Caller must have the adjust limits role (`RoleType::AdjustLimits`)
+        /// `None` removes the limit
+        #[ink(message)]
+        fn adjust_max_account_shares(&mut self, new_max: Option<u128>) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::AdjustLimits)?;
+            if self.data.max_account_shares == new_max {
+                return Err(VaultError::NoChange);
+            }
+
+            self.data.max_account_shares = new_max;
+
+            Self::emit_event(
+                Self::env(),
+                Event::MaxAccountSharesAdjusted(MaxAccountSharesAdjusted {
+                    new_max,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Pauses an operation
+        ///
+        /// Caller must have the pause guardian role (`RoleType::PauseGuardian`)
+        /// Redemption of unlock requests is never paused
+        #[ink(message)]
+        fn pause(&mut self, pause_type: PauseType) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::PauseGuardian)?;
+            if self.data.paused.contains(pause_type) {
+                return Err(VaultError::NoChange);
             }
-            if role_adjust_fee == new_account {
+
+            self.data.paused.insert(pause_type, &());
+
+            Self::emit_event(
+                Self::env(),
+                Event::Paused(Paused {
+                    pause_type,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Unpauses an operation
+        ///
+        /// Caller must have the admin role (`RoleType::Admin`)
+        #[ink(message)]
+        fn unpause(&mut self, pause_type: PauseType) -> Result<(), VaultError> {
+            self.ensure_role(RoleType::Admin)?;
+            if !self.data.paused.contains(pause_type) {
                 return Err(VaultError::NoChange);
             }
 
-            self.data.role_adjust_fee = new_account;
+            self.data.paused.remove(pause_type);
 
             Self::emit_event(
                 Self::env(),
-                Event::RoleAdjustFeeTransferred(RoleAdjustFeeTransferred {
-                    new_account,
+                Event::Unpaused(Unpaused {
+                    pause_type,
                 }),
             );
 
@@ -511,31 +1986,104 @@ mod vault {
         }
 
         #[ink(message)]
-        fn get_role_fee_to(&self) -> AccountId {
-            self.data.role_fee_to
+        fn is_paused(&self, pause_type: PauseType) -> bool {
+            self.data.paused.contains(pause_type)
+        }
+
+        /// Proposes granting a role to an account
+        ///
+        /// Caller must have the admin role of the role, `RoleType::Admin` unless the role is `RoleType::SetCode`
+        /// and upgrades have been disabled
+        /// The account must call `accept_role` to become a member
+        #[ink(message)]
+        fn propose_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), VaultError> {
+            self.data
+                .access_control
+                .propose_role(Self::env().caller(), role_type.id(), account)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::RoleGrantProposed(RoleGrantProposed {
+                    role_type,
+                    account,
+                }),
+            );
+
+            Ok(())
         }
 
-        /// Transfers fee to role to a new account
+        /// Completes a role grant
         ///
-        /// Caller must have the fee to role (`role_fee_to`)
+        /// Caller must be the proposed account
         #[ink(message)]
-        fn transfer_role_fee_to(&mut self, new_account: AccountId) -> Result<(), VaultError> {
+        fn accept_role(&mut self, role_type: RoleType) -> Result<(), VaultError> {
             let caller = Self::env().caller();
-            let role_fee_to = self.data.role_fee_to; // shadow
 
-            if caller != role_fee_to {
-                return Err(VaultError::InvalidPermissions);
-            }
-            if role_fee_to == new_account {
-                return Err(VaultError::NoChange);
-            }
+            self.data.access_control.accept_role(caller, role_type.id())?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::RoleGranted(RoleGranted {
+                    role_type,
+                    account: caller,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Cancels a pending role grant
+        ///
+        /// Caller must have the admin role of the role
+        #[ink(message)]
+        fn cancel_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), VaultError> {
+            self.data
+                .access_control
+                .cancel_role(Self::env().caller(), role_type.id(), account)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::RoleGrantCancelled(RoleGrantCancelled {
+                    role_type,
+                    account,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Removes an account from a role
+        ///
+        /// Caller must have the admin role of the role
+        #[ink(message)]
+        fn revoke_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), VaultError> {
+            self.data
+                .access_control
+                .revoke_role(Self::env().caller(), role_type.id(), account)?;
+
+            Self::emit_event(
+                Self::env(),
+                Event::RoleRevoked(RoleRevoked {
+                    role_type,
+                    account,
+                }),
+            );
+
+            Ok(())
+        }
+
+        /// Removes the caller from a role
+        #[ink(message)]
+        fn renounce_role(&mut self, role_type: RoleType) -> Result<(), VaultError> {
+            let caller = Self::env().caller();
 
-            self.data.role_fee_to = new_account;
+            self.data.access_control.renounce_role(caller, role_type.id())?;
 
             Self::emit_event(
                 Self::env(),
-                Event::RoleFeeToTransferred(RoleFeeToTransferred {
-                    new_account,
+                Event::RoleRevoked(RoleRevoked {
+                    role_type,
+                    account: caller,
                 }),
             );
 
@@ -543,11 +2091,66 @@ mod vault {
         }
 
         #[ink(message)]
-        fn get_role_set_code(&self) -> Option<AccountId> {
-            self.data.role_set_code
+        fn has_role(&self, role_type: RoleType, account: AccountId) -> bool {
+            self.data.has_role(role_type, account)
+        }
+
+        #[ink(message)]
+        fn get_role_members(&self, role_type: RoleType) -> Vec<AccountId> {
+            self.data.access_control.get_role_members(role_type.id())
+        }
+
+        #[ink(message)]
+        fn is_pending_role(&self, role_type: RoleType, account: AccountId) -> bool {
+            self.data.access_control.is_pending(role_type.id(), account)
+        }
+
+        #[ink(message)]
+        fn get_max_total_pooled(&self) -> Balance {
+            self.data.max_total_pooled
+        }
+
+        #[ink(message)]
+        fn get_max_account_shares(&self) -> Option<u128> {
+            self.data.max_account_shares
+        }
+
+        /// Returns the AZERO which can still be staked before reaching `max_total_pooled`
+        #[ink(message)]
+        fn get_remaining_pool_capacity(&self) -> Balance {
+            self.data.max_total_pooled.saturating_sub(self.data.total_pooled)
+        }
+
+        /// Returns the sA0 an account can still receive by staking, `None` when unlimited
+        #[ink(message)]
+        fn get_remaining_account_capacity(&self, account: AccountId) -> Option<u128> {
+            self.data
+                .max_account_shares
+                .map(|max| max.saturating_sub(self.shares_of(&account)))
+        }
+
+        #[ink(message)]
+        fn get_code_announcement(&self) -> Option<CodeAnnouncement> {
+            self.data.code_announcement.clone()
+        }
+
+        #[ink(message)]
+        fn get_code_delay(&self) -> u64 {
+            self.data.code_delay
+        }
+
+        #[ink(message)]
+        fn get_code_delay_proposal(&self) -> Option<CodeDelayProposal> {
+            self.data.code_delay_proposal.clone()
+        }
+
+        /// Returns the shortest allowed code delay, also bounding the Registry's code delay
+        #[ink(message)]
+        fn get_min_code_delay(&self) -> u64 {
+            self.data.min_code_delay()
         }
 
-        /// Returns the total amount of bonded AZERO
+        /// Returns the total amount of pooled AZERO including the idle buffer
         #[ink(message)]
         fn get_total_pooled(&self) -> Balance {
             self.data.total_pooled
@@ -558,7 +2161,10 @@ mod vault {
         ///     2) sA0 that could be minted (virtual) representing accumulating protocol fees
         #[ink(message)]
         fn get_total_shares(&self) -> u128 {
-            self.data.total_shares_minted + self.get_current_virtual_shares()
+            let now = Self::env().block_timestamp();
+            self.data.total_shares_minted
+                + self.data.get_virtual_shares_at_time(now)
+                + self.data.get_referral_shares_at_time(now)
         }
 
         /// Protocol fees (sA0) which can be minted and withdrawn at the current block timestamp
@@ -572,12 +2178,120 @@ mod vault {
         fn get_fee_percentage(&self) -> u16 {
             self.data.fee_percentage
         }
-        
+
+        #[ink(message)]
+        fn get_fee_mode(&self) -> FeeMode {
+            self.data.fee_mode
+        }
+
+        #[ink(message)]
+        fn get_fee_proposal(&self) -> Option<FeeProposal> {
+            self.data.fee_proposal.clone()
+        }
+
+        #[ink(message)]
+        fn get_fee_mode_proposal(&self) -> Option<FeeModeProposal> {
+            self.data.fee_mode_proposal.clone()
+        }
+
+        #[ink(message)]
+        fn get_fee_delay(&self) -> u64 {
+            self.data.fee_delay
+        }
+
+        #[ink(message)]
+        fn get_fee_delay_proposal(&self) -> Option<FeeDelayProposal> {
+            self.data.fee_delay_proposal.clone()
+        }
+
+        #[ink(message)]
+        fn get_referral_fee_cut(&self) -> u16 {
+            self.data.referral_fee_cut
+        }
+
+        #[ink(message)]
+        fn get_referrer(&self, referrer: AccountId) -> Referrer {
+            self.data.referrers.get(referrer).unwrap_or_default()
+        }
+
+        /// Returns the referrer and referred shares of a staker, `None` when the staker was not referred
+        #[ink(message)]
+        fn get_referred_stake(&self, staker: AccountId) -> Option<ReferredStake> {
+            self.data.referred_stakes.get(staker)
+        }
+
+        /// Referral rewards (sA0) which can be claimed by a referrer at the current block timestamp
+        #[ink(message)]
+        fn get_claimable_referral_rewards(&self, referrer: AccountId) -> u128 {
+            let referrer = self.data.referrers.get(referrer).unwrap_or_default();
+            self.data.get_referral_rewards_at_time(&referrer, Self::env().block_timestamp())
+        }
+
+        #[ink(message)]
+        fn get_buffer_target(&self) -> u16 {
+            self.data.buffer_target
+        }
+
+        /// Returns the idle AZERO available for instant unlocks
+        #[ink(message)]
+        fn get_buffer_reserve(&self) -> Balance {
+            self.data.buffer_reserve
+        }
+
+        /// Returns the index of the next agent to compound via `compound_range`
+        #[ink(message)]
+        fn get_compound_cursor(&self) -> u64 {
+            self.data.compound_cursor
+        }
+
+        /// Returns the index of the next agent to withdraw from via `withdraw_unbonded_range`
+        #[ink(message)]
+        fn get_withdraw_cursor(&self) -> u64 {
+            self.data.withdraw_cursor
+        }
+
+        /// Returns the AZERO being unbonded by the in-flight rebalance
+        #[ink(message)]
+        fn get_total_rebalancing(&self) -> Balance {
+            self.data.total_rebalancing
+        }
+
+        /// Returns the time the in-flight rebalance began unbonding, `None` when no rebalance is in flight
+        #[ink(message)]
+        fn get_rebalance_time(&self) -> Option<Timestamp> {
+            self.data.rebalance_time
+        }
+
+        #[ink(message)]
+        fn get_instant_unlock_fee(&self) -> u16 {
+            self.data.instant_unlock_fee
+        }
+
+        /// Returns the compound bounty in basis points, its maximum AZERO payout, and the minimum payout interval
+        #[ink(message)]
+        fn get_compound_bounty(&self) -> (u16, Balance, u64) {
+            (
+                self.data.compound_bounty,
+                self.data.max_compound_bounty,
+                self.data.compound_bounty_interval,
+            )
+        }
+
+        #[ink(message)]
+        fn get_compound_bounty_proposal(&self) -> Option<CompoundBountyProposal> {
+            self.data.compound_bounty_proposal.clone()
+        }
+
         #[ink(message)]
         fn get_share_token_contract(&self) -> AccountId {
             self.data.shares_contract
         }
 
+        #[ink(message)]
+        fn get_unlock_ticket_contract(&self) -> AccountId {
+            self.data.ticket_contract
+        }
+
         #[ink(message)]
         fn get_registry_contract(&self) -> AccountId {
             RegistryRef::to_account_id(&self.data.registry_contract)
@@ -609,10 +2323,80 @@ mod vault {
             }
         }
 
-        /// Returns the unlock requests for a given user
+        /// Returns the time at which a pending unlock request can be redeemed
+        ///
+        /// Requests within an unprocessed batch assume the batch is processed as early as possible
+        /// Returns `None` when the request does not exist or is no longer pending
+        #[ink(message)]
+        fn get_claimable_time(&self, user: AccountId, unlock_id: u64) -> Option<Timestamp> {
+            let unlock_request = self.data.user_unlock_requests.get((user, unlock_id))?;
+            if unlock_request.status != UnlockStatus::Pending {
+                return None;
+            }
+
+            let batch = self.data.batch_unlock_requests.get(unlock_request.batch_id).unwrap_or_default();
+            let process_time = match batch.process_time {
+                Some(t) => t,
+                None => self.data.last_batch_process_time + self.data.batch_interval,
+            };
+
+            Some(process_time + self.data.cooldown_period())
+        }
+
+        /// Returns the AZERO a pending unlock request can redeem
+        ///
+        /// Processed requests reflect any slash charged to their batch while unbonding
+        /// Requests within an unprocessed batch are valued at the current share price
+        /// Returns `None` when the request does not exist or is no longer pending
+        #[ink(message)]
+        fn get_claimable_azero(&self, user: AccountId, unlock_id: u64) -> Option<Balance> {
+            let unlock_request = self.data.user_unlock_requests.get((user, unlock_id))?;
+            if unlock_request.status != UnlockStatus::Pending {
+                return None;
+            }
+
+            let batch = self.data.batch_unlock_requests.get(unlock_request.batch_id).unwrap_or_default();
+            match batch.process_time {
+                Some(_) => Some(self.data.pro_rata(batch.total_azero, unlock_request.shares, batch.total_shares)),
+                None => Some(self.get_azero_from_shares(unlock_request.shares)),
+            }
+        }
+
+        /// Returns a single unlock request of a given user
+        #[ink(message)]
+        fn get_unlock_request(&self, user: AccountId, unlock_id: u64) -> Option<UnlockRequest> {
+            self.data.user_unlock_requests.get((user, unlock_id))
+        }
+
+        /// Returns the number of unlock requests ever created by a given user
+        #[ink(message)]
+        fn get_unlock_request_count(&self, user: AccountId) -> u64 {
+            self.data.user_unlock_request_count.get(user).unwrap_or(0)
+        }
+
+        /// Returns up to `limit` unlock requests of a given user starting at unlock id `start`
+        ///
+        /// Each entry is paired with its unlock id
+        #[ink(message)]
+        fn get_unlock_requests(&self, user: AccountId, start: u64, limit: u64) -> Vec<(u64, UnlockRequest)> {
+            let count = self.get_unlock_request_count(user);
+            let end = start.saturating_add(limit.min(MAX_UNLOCK_REQUESTS_PAGE)).min(count);
+            (start..end)
+                .filter_map(|unlock_id| {
+                    self.data.user_unlock_requests.get((user, unlock_id)).map(|r| (unlock_id, r))
+                })
+                .collect()
+        }
+
+        /// Returns the batch currently accepting unlock requests
+        #[ink(message)]
+        fn get_current_batch_id(&self) -> u64 {
+            self.data.current_batch_id
+        }
+
         #[ink(message)]
-        fn get_unlock_requests(&self, user: AccountId) -> Vec<UnlockRequest> {
-            self.data.user_unlock_requests.get(user).unwrap_or_default()
+        fn get_batch_unlock_request(&self, batch_id: u64) -> Option<UnlockBatch> {
+            self.data.batch_unlock_requests.get(batch_id)
         }
 
         #[ink(message)]
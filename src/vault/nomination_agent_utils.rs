@@ -14,6 +14,8 @@ pub enum RuntimeError {
     CallRuntimeFailed,
     Unauthorized,
     Active,
+    InvalidSlash,
+    InvalidValidators,
 }
 
 type Balance = <DefaultEnvironment as Environment>::Balance;
@@ -21,6 +23,9 @@ const DEPOSIT_SELECTOR: Selector = Selector::new([0, 0, 0, 1]);
 const UNBOND_SELECTOR: Selector = Selector::new([0, 0, 0, 2]);
 const WITHDRAW_SELECTOR: Selector = Selector::new([0, 0, 0, 3]);
 const COMPOUND_SELECTOR: Selector = Selector::new( [0, 0, 0, 4]);
+const REBOND_SELECTOR: Selector = Selector::new([0, 0, 0, 5]);
+const SLASH_SELECTOR: Selector = Selector::new([0, 0, 0, 6]);
+const PAYOUT_SELECTOR: Selector = Selector::new([0, 0, 0, 7]);
 const QUERY_STAKED_VALUE_SELECTOR: Selector = Selector::new([0, 0, 0, 12]);
 
 pub fn make_call(
@@ -51,6 +56,37 @@ pub fn call_unbond(nomination_agent_instance: AccountId, amount: u128) -> Result
         .invoke()
 }
 
+pub fn call_rebond(nomination_agent_instance: AccountId, amount: u128) -> Result<(), RuntimeError> {
+    build_call::<DefaultEnvironment>()
+        .call(nomination_agent_instance)
+        .exec_input(ExecutionInput::new(REBOND_SELECTOR).push_arg(amount))
+        .transferred_value(0)
+        .returns::<Result<(), RuntimeError>>()
+        .invoke()
+}
+
+pub fn call_slash(
+    nomination_agent_instance: AccountId,
+    staked: u128,
+    unbonding: u128,
+) -> Result<(Balance, Balance), RuntimeError> {
+    build_call::<DefaultEnvironment>()
+        .call(nomination_agent_instance)
+        .exec_input(ExecutionInput::new(SLASH_SELECTOR).push_arg(staked).push_arg(unbonding))
+        .transferred_value(0)
+        .returns::<Result<(Balance, Balance), RuntimeError>>()
+        .invoke()
+}
+
+pub fn call_payout(nomination_agent_instance: AccountId, era: u32) -> Result<(), RuntimeError> {
+    build_call::<DefaultEnvironment>()
+        .call(nomination_agent_instance)
+        .exec_input(ExecutionInput::new(PAYOUT_SELECTOR).push_arg(era))
+        .transferred_value(0)
+        .returns::<Result<(), RuntimeError>>()
+        .invoke()
+}
+
 pub fn call_withdraw_unbonded(nomination_agent_instance: AccountId) -> Result<(), RuntimeError> {
     make_call(nomination_agent_instance, WITHDRAW_SELECTOR, 0_u128)
 }
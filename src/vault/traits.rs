@@ -1,4 +1,4 @@
-use crate::data::{Balance, UnlockRequest};
+use crate::data::{Balance, CodeAnnouncement, CompoundBountyProposal, CodeDelayProposal, FeeDelayProposal, FeeMode, FeeModeProposal, FeeProposal, PauseType, ReferredStake, Referrer, RoleType, Timestamp, UnlockBatch, UnlockRequest};
 use crate::errors::VaultError;
 use ink::{
     primitives::AccountId,
@@ -25,47 +25,225 @@ pub trait IVault {
     #[ink(message, payable)]
     fn stake_with_referral(&mut self, referral_id: AccountId) -> Result<u128, VaultError>;
 
+    #[ink(message, payable)]
+    fn stake_for(&mut self, beneficiary: AccountId) -> Result<u128, VaultError>;
+
+    #[ink(message, payable)]
+    fn stake_for_with_referral(&mut self, beneficiary: AccountId, referral_id: AccountId) -> Result<u128, VaultError>;
+
     #[ink(message)]
     fn request_unlock(&mut self, shares: u128) -> Result<(), VaultError>;
 
+    #[ink(message)]
+    fn process_batch(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn cancel_unlock(&mut self, unlock_id: u64) -> Result<u128, VaultError>;
+
+    #[ink(message)]
+    fn instant_unlock(&mut self, shares: u128, min_azero: Balance) -> Result<Balance, VaultError>;
+
     #[ink(message)]
     fn delegate_withdraw_unbonded(&mut self) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn redeem(&mut self, user: AccountId, unlock_id: u64) -> Result<(), VaultError>;
+    fn withdraw_unbonded_range(&mut self, start: u64, count: u64) -> Result<u64, VaultError>;
 
     #[ink(message)]
-    fn redeem_with_withdraw(&mut self, user: AccountId, unlock_id: u64) -> Result<(), VaultError>;
+    fn redeem(&mut self, user: AccountId, unlock_id: u64) -> Result<Balance, VaultError>;
+
+    #[ink(message)]
+    fn redeem_with_withdraw(&mut self, user: AccountId, unlock_id: u64) -> Result<Balance, VaultError>;
 
     #[ink(message)]
     fn compound(&mut self) -> Result<Balance, VaultError>;
 
+    #[ink(message)]
+    fn payout_and_compound(&mut self, eras: Vec<u32>, start: u64, count: u64) -> Result<Balance, VaultError>;
+
+    #[ink(message)]
+    fn compound_range(&mut self, start: u64, count: u64) -> Result<Balance, VaultError>;
+
+    #[ink(message)]
+    fn report_slash(
+        &mut self,
+        agent: AccountId,
+        staked: Balance,
+        unbonding: Balance,
+    ) -> Result<(Balance, Balance), VaultError>;
+
+    #[ink(message)]
+    fn rebalance(&mut self, max_amount: Balance) -> Result<Balance, VaultError>;
+
+    #[ink(message)]
+    fn complete_rebalance(&mut self) -> Result<Balance, VaultError>;
+
+    #[ink(message)]
+    fn distribute_fees(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn set_fee_recipients(&mut self, recipients: Vec<(AccountId, u16)>) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_fee_recipients(&self) -> Vec<(AccountId, u16)>;
+
     #[ink(message)]
     fn withdraw_fees(&mut self) -> Result<(), VaultError>;
 
+    #[ink(message)]
+    fn claim_referral_rewards(&mut self) -> Result<u128, VaultError>;
+
+    #[ink(message)]
+    fn sync_referred_stake(&mut self, staker: AccountId) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn announce_code(&mut self, code_hash: [u8; 32]) -> Result<(), VaultError>;
+
     #[ink(message)]
     fn set_code(&mut self, code_hash: [u8; 32]) -> Result<(), VaultError>;
 
+    #[ink(message)]
+    fn cancel_code(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn adjust_code_delay(&mut self, new_delay: u64) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn execute_code_delay(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_code_announcement(&self) -> Option<CodeAnnouncement>;
+
+    #[ink(message)]
+    fn get_code_delay(&self) -> u64;
+
+    #[ink(message)]
+    fn get_code_delay_proposal(&self) -> Option<CodeDelayProposal>;
+
+    #[ink(message)]
+    fn get_min_code_delay(&self) -> u64;
+
     #[ink(message)]
     fn disable_set_code(&mut self) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn adjust_fee(&mut self, new_fee: u16) -> Result<(), VaultError>;
+    fn propose_fee(&mut self, new_fee: u16) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn execute_fee(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn cancel_fee(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn adjust_fee_delay(&mut self, new_delay: u64) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn execute_fee_delay(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn propose_fee_mode(&mut self, new_mode: FeeMode) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn execute_fee_mode(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn cancel_fee_mode(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn adjust_referral_fee_cut(&mut self, new_cut: u16) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn adjust_buffer_target(&mut self, new_target: u16) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn adjust_instant_unlock_fee(&mut self, new_fee: u16) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn adjust_compound_bounty(
+        &mut self,
+        new_bounty: u16,
+        new_max_bounty: Balance,
+        new_interval: u64,
+    ) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn execute_compound_bounty(&mut self) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn adjust_cooldown(&mut self, era_duration: u64, bonding_eras: u64) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_era_duration(&self) -> u64;
+
+    #[ink(message)]
+    fn get_bonding_eras(&self) -> u64;
+
+    #[ink(message)]
+    fn get_cooldown_period(&self) -> u64;
+
+    #[ink(message)]
+    fn adjust_minimum_stake(&mut self, new_minimum: Balance) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn adjust_minimum_unlock(&mut self, new_minimum: u128) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn get_minimum_stake(&self) -> Balance;
+
+    #[ink(message)]
+    fn get_minimum_unlock(&self) -> u128;
+
+    #[ink(message)]
+    fn adjust_max_total_pooled(&mut self, new_max: Balance) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn adjust_max_account_shares(&mut self, new_max: Option<u128>) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn pause(&mut self, pause_type: PauseType) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn unpause(&mut self, pause_type: PauseType) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn is_paused(&self, pause_type: PauseType) -> bool;
+
+    #[ink(message)]
+    fn propose_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), VaultError>;
+
+    #[ink(message)]
+    fn accept_role(&mut self, role_type: RoleType) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn get_role_adjust_fee(&self) -> AccountId;
+    fn cancel_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn transfer_role_adjust_fee(&mut self, new_account: AccountId) -> Result<(), VaultError>;
+    fn revoke_role(&mut self, role_type: RoleType, account: AccountId) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn get_role_fee_to(&self) -> AccountId;
+    fn renounce_role(&mut self, role_type: RoleType) -> Result<(), VaultError>;
 
     #[ink(message)]
-    fn transfer_role_fee_to(&mut self, new_account: AccountId) -> Result<(), VaultError>;
+    fn has_role(&self, role_type: RoleType, account: AccountId) -> bool;
 
     #[ink(message)]
-    fn get_role_set_code(&self) -> Option<AccountId>;
+    fn get_role_members(&self, role_type: RoleType) -> Vec<AccountId>;
+
+    #[ink(message)]
+    fn is_pending_role(&self, role_type: RoleType, account: AccountId) -> bool;
+
+    #[ink(message)]
+    fn get_max_total_pooled(&self) -> Balance;
+
+    #[ink(message)]
+    fn get_max_account_shares(&self) -> Option<u128>;
+
+    #[ink(message)]
+    fn get_remaining_pool_capacity(&self) -> Balance;
+
+    #[ink(message)]
+    fn get_remaining_account_capacity(&self, account: AccountId) -> Option<u128>;
 
     #[ink(message)]
     fn get_total_pooled(&self) -> Balance;
@@ -79,9 +257,66 @@ pub trait IVault {
     #[ink(message)]
     fn get_fee_percentage(&self) -> u16;
 
+    #[ink(message)]
+    fn get_fee_mode(&self) -> FeeMode;
+
+    #[ink(message)]
+    fn get_fee_proposal(&self) -> Option<FeeProposal>;
+
+    #[ink(message)]
+    fn get_fee_mode_proposal(&self) -> Option<FeeModeProposal>;
+
+    #[ink(message)]
+    fn get_fee_delay(&self) -> u64;
+
+    #[ink(message)]
+    fn get_fee_delay_proposal(&self) -> Option<FeeDelayProposal>;
+
+    #[ink(message)]
+    fn get_referral_fee_cut(&self) -> u16;
+
+    #[ink(message)]
+    fn get_referrer(&self, referrer: AccountId) -> Referrer;
+
+    #[ink(message)]
+    fn get_referred_stake(&self, staker: AccountId) -> Option<ReferredStake>;
+
+    #[ink(message)]
+    fn get_claimable_referral_rewards(&self, referrer: AccountId) -> u128;
+
+    #[ink(message)]
+    fn get_buffer_target(&self) -> u16;
+
+    #[ink(message)]
+    fn get_buffer_reserve(&self) -> Balance;
+
+    #[ink(message)]
+    fn get_compound_cursor(&self) -> u64;
+
+    #[ink(message)]
+    fn get_withdraw_cursor(&self) -> u64;
+
+    #[ink(message)]
+    fn get_total_rebalancing(&self) -> Balance;
+
+    #[ink(message)]
+    fn get_rebalance_time(&self) -> Option<Timestamp>;
+
+    #[ink(message)]
+    fn get_instant_unlock_fee(&self) -> u16;
+
+    #[ink(message)]
+    fn get_compound_bounty(&self) -> (u16, Balance, u64);
+
+    #[ink(message)]
+    fn get_compound_bounty_proposal(&self) -> Option<CompoundBountyProposal>;
+
     #[ink(message)]
     fn get_share_token_contract(&self) -> AccountId;
 
+    #[ink(message)]
+    fn get_unlock_ticket_contract(&self) -> AccountId;
+
     #[ink(message)]
     fn get_registry_contract(&self) -> AccountId;
 
@@ -92,7 +327,25 @@ pub trait IVault {
     fn get_azero_from_shares(&self, shares: u128) -> Balance;
 
     #[ink(message)]
-    fn get_unlock_requests(&self, user: AccountId) -> Vec<UnlockRequest>;
+    fn get_claimable_time(&self, user: AccountId, unlock_id: u64) -> Option<Timestamp>;
+
+    #[ink(message)]
+    fn get_claimable_azero(&self, user: AccountId, unlock_id: u64) -> Option<Balance>;
+
+    #[ink(message)]
+    fn get_unlock_request(&self, user: AccountId, unlock_id: u64) -> Option<UnlockRequest>;
+
+    #[ink(message)]
+    fn get_unlock_request_count(&self, user: AccountId) -> u64;
+
+    #[ink(message)]
+    fn get_unlock_requests(&self, user: AccountId, start: u64, limit: u64) -> Vec<(u64, UnlockRequest)>;
+
+    #[ink(message)]
+    fn get_current_batch_id(&self) -> u64;
+
+    #[ink(message)]
+    fn get_batch_unlock_request(&self, batch_id: u64) -> Option<UnlockBatch>;
 
     #[ink(message)]
     fn get_weight_imbalances(&self, total_pooled: u128) -> (u128, u128, Vec<u128>, Vec<i128>);